@@ -0,0 +1,48 @@
+//! Compares rendering with a freshly built font against the shared built-in font
+//!
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use text_to_input::{PixelFont, RenderOptions, Renderer};
+
+const TEXT: &str = "Hello World";
+const ITERATIONS: u32 = 20_000;
+
+/// Average time per call of `f` over `ITERATIONS` calls, after a short warm-up
+fn time_per_call(mut f: impl FnMut()) -> Duration {
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let options = RenderOptions::default();
+    let rebuilt = time_per_call(|| {
+        let font = PixelFont::new();
+        black_box(font.render(black_box(TEXT), &options).unwrap());
+    });
+
+    let renderer = Renderer::default();
+    let cached = time_per_call(|| {
+        black_box(renderer.render(black_box(TEXT)).unwrap());
+    });
+
+    let free_function = time_per_call(|| {
+        black_box(text_to_input::render(black_box(TEXT)).unwrap());
+    });
+
+    println!("font rebuilt per call:   {:>10?}", rebuilt);
+    println!("Renderer (cached font):  {:>10?}", cached);
+    println!("render() (cached font):  {:>10?}", free_function);
+    println!(
+        "speedup:                 {:>9.1}x",
+        rebuilt.as_secs_f64() / cached.as_secs_f64()
+    );
+}
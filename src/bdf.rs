@@ -0,0 +1,368 @@
+//! Import of X11 Bitmap Distribution Format (BDF) fonts
+//!
+//! Every glyph is placed into a cell as tall as the font (`FONT_ASCENT` plus
+//! `FONT_DESCENT`, or the font bounding box) and as wide as its `DWIDTH`
+//! advance, so the glyph's bounding box offsets relative to the baseline are
+//! preserved. BDF advances normally include the gap between
+//! characters, so such fonts usually look best with a letter spacing of 0.
+
+use std::io::BufRead;
+
+use crate::{CharacterPattern, FontError, ParseErrorKind, PixelFont};
+
+/// Largest glyph width or font height accepted, in pixels
+const MAX_GLYPH_SIZE: i32 = 1024;
+
+/// Font-wide metrics collected from the header
+#[derive(Default)]
+struct Header {
+    bounding_box: Option<BoundingBox>,
+    ascent: Option<i32>,
+    descent: Option<i32>,
+}
+
+/// A `FONTBOUNDINGBOX` or `BBX` entry
+#[derive(Clone, Copy)]
+struct BoundingBox {
+    width: i32,
+    height: i32,
+    x_offset: i32,
+    y_offset: i32,
+}
+
+/// A glyph being collected while parsing
+#[derive(Default)]
+struct PendingGlyph {
+    encoding: Option<i32>,
+    advance: Option<i32>,
+    bounding_box: Option<BoundingBox>,
+    rows: Vec<Vec<u8>>,
+}
+
+/// Parse a BDF font
+///
+/// Lines are decoded leniently, as properties such as `COPYRIGHT` are often
+/// Latin-1 while everything the parser reads is ASCII.
+pub(crate) fn parse<R: BufRead>(mut reader: R) -> Result<PixelFont, FontError> {
+    let mut header = Header::default();
+    let mut font: Option<PixelFont> = None;
+    let mut glyph: Option<PendingGlyph> = None;
+    let mut in_bitmap = false;
+    let mut line_number = 0;
+    let mut buffer = Vec::new();
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line_number += 1;
+        let line = String::from_utf8_lossy(&buffer);
+        let mut fields = line.split_whitespace();
+        let Some(keyword) = fields.next() else {
+            continue;
+        };
+        let values: Vec<&str> = fields.collect();
+
+        if in_bitmap {
+            if keyword == "ENDCHAR" {
+                in_bitmap = false;
+            } else if let Some(glyph) = glyph.as_mut() {
+                glyph.rows.push(parse_hex_row(keyword, line_number)?);
+                continue;
+            }
+        }
+
+        match keyword {
+            "FONTBOUNDINGBOX" => {
+                header.bounding_box = Some(parse_bounding_box(&values, line_number)?);
+            }
+            "FONT_ASCENT" => header.ascent = Some(parse_number(&values, 0, line_number)?),
+            "FONT_DESCENT" => header.descent = Some(parse_number(&values, 0, line_number)?),
+            "STARTCHAR" => {
+                if font.is_none() {
+                    let (ascent, descent) = font_metrics(&header, line_number)?;
+                    font = Some(PixelFont::with_metrics(ascent, descent));
+                }
+                glyph = Some(PendingGlyph::default());
+            }
+            "ENCODING" => {
+                if let Some(glyph) = glyph.as_mut() {
+                    glyph.encoding = Some(parse_number(&values, 0, line_number)?);
+                }
+            }
+            "DWIDTH" => {
+                if let Some(glyph) = glyph.as_mut() {
+                    glyph.advance = Some(parse_number(&values, 0, line_number)?);
+                }
+            }
+            "BBX" => {
+                if let Some(glyph) = glyph.as_mut() {
+                    glyph.bounding_box = Some(parse_bounding_box(&values, line_number)?);
+                }
+            }
+            "BITMAP" => {
+                if glyph.is_none() {
+                    return Err(parse_error(line_number, ParseErrorKind::MissingHeader));
+                }
+                in_bitmap = true;
+            }
+            "ENDCHAR" => {
+                let (Some(pending), Some(font)) = (glyph.take(), font.as_mut()) else {
+                    return Err(parse_error(line_number, ParseErrorKind::MissingHeader));
+                };
+                if let Some((ch, pattern)) = finish_glyph(pending, &header, font, line_number)? {
+                    if font.get_pattern(ch).is_some() {
+                        return Err(parse_error(line_number, ParseErrorKind::DuplicateGlyph(ch)));
+                    }
+                    font.insert(ch, pattern);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if glyph.is_some() {
+        return Err(parse_error(line_number, ParseErrorKind::UnexpectedEnd));
+    }
+    Ok(font.unwrap_or_else(PixelFont::empty))
+}
+
+/// Work out the ascent and descent shared by every glyph
+fn font_metrics(header: &Header, line: usize) -> Result<(usize, usize), FontError> {
+    let invalid = |value: i32| parse_error(line, ParseErrorKind::InvalidValue(value.to_string()));
+    let (ascent, descent) = match (header.ascent, header.descent, header.bounding_box) {
+        (Some(ascent), Some(descent), _) => (ascent, descent),
+        (_, _, Some(bbx)) => (
+            bbx.height
+                .checked_add(bbx.y_offset)
+                .ok_or_else(|| invalid(bbx.y_offset))?,
+            bbx.y_offset
+                .checked_neg()
+                .ok_or_else(|| invalid(bbx.y_offset))?,
+        ),
+        _ => {
+            return Err(parse_error(
+                line,
+                ParseErrorKind::MissingProperty("FONTBOUNDINGBOX".to_string()),
+            ))
+        }
+    };
+    let height = ascent
+        .checked_add(descent)
+        .ok_or_else(|| invalid(descent))?;
+    if ascent < 0 || descent < 0 || height == 0 || height > MAX_GLYPH_SIZE {
+        return Err(invalid(height));
+    }
+    Ok((ascent as usize, descent as usize))
+}
+
+/// The right shift keeping a glyph's pixels at or right of the origin, and
+/// the width of its cell, or `None` if either overflows
+fn glyph_columns(bbx: BoundingBox, advance: Option<i32>) -> Option<(i32, i32)> {
+    let x_shift = bbx.x_offset.checked_neg()?.max(0);
+    let advance = match advance {
+        Some(advance) => advance,
+        None => bbx.width.checked_add(bbx.x_offset)?,
+    };
+    let right = bbx.x_offset.checked_add(x_shift)?.checked_add(bbx.width)?;
+    Some((x_shift, advance.checked_add(x_shift)?.max(right).max(1)))
+}
+
+/// Turn a completed glyph into a character pattern
+///
+/// Returns `None` for glyphs that do not map to a renderable character.
+fn finish_glyph(
+    glyph: PendingGlyph,
+    header: &Header,
+    font: &PixelFont,
+    line: usize,
+) -> Result<Option<(char, CharacterPattern)>, FontError> {
+    let height = font.height();
+    let ascent = font.ascent() as i32;
+    let encoding = glyph.encoding.ok_or_else(|| {
+        parse_error(
+            line,
+            ParseErrorKind::MissingProperty("ENCODING".to_string()),
+        )
+    })?;
+    let ch = match u32::try_from(encoding).ok().and_then(char::from_u32) {
+        // Spaces are laid out using the word spacing instead of a glyph
+        Some(' ') | None => return Ok(None),
+        Some(ch) => ch,
+    };
+
+    let bbx = glyph
+        .bounding_box
+        .or(header.bounding_box)
+        .ok_or_else(|| parse_error(line, ParseErrorKind::MissingProperty("BBX".to_string())))?;
+    if glyph.rows.len() != bbx.height.max(0) as usize {
+        return Err(parse_error(
+            line,
+            ParseErrorKind::WrongRowCount {
+                expected: bbx.height.max(0) as usize,
+                actual: glyph.rows.len(),
+            },
+        ));
+    }
+
+    // Glyphs drawn left of the origin are shifted right so nothing is lost
+    let invalid = |value: i32| parse_error(line, ParseErrorKind::InvalidValue(value.to_string()));
+    let (x_shift, width) = glyph_columns(bbx, glyph.advance)
+        .filter(|&(_, width)| width <= MAX_GLYPH_SIZE)
+        .ok_or_else(|| invalid(glyph.advance.unwrap_or(bbx.width)))?;
+    let top = bbx
+        .y_offset
+        .checked_add(bbx.height)
+        .and_then(|bottom| ascent.checked_sub(bottom))
+        .ok_or_else(|| invalid(bbx.y_offset))?;
+
+    let mut pixels = vec![vec![0u8; width as usize]; height];
+    for (row_idx, row) in glyph.rows.iter().enumerate() {
+        let y = i64::from(top) + row_idx as i64;
+        if y < 0 || y >= height as i64 {
+            continue;
+        }
+        for col in 0..bbx.width.max(0) as usize {
+            if row.get(col).copied() == Some(1) {
+                let x = (bbx.x_offset + x_shift) as usize + col;
+                pixels[y as usize][x] = 1;
+            }
+        }
+    }
+
+    let pattern = CharacterPattern::from_pixels(pixels).with_baseline(ascent as usize);
+    Ok(Some((ch, pattern)))
+}
+
+/// Decode one hex-encoded bitmap row into pixels, most significant bit first
+fn parse_hex_row(hex: &str, line: usize) -> Result<Vec<u8>, FontError> {
+    let mut pixels = Vec::with_capacity(hex.len() * 4);
+    for digit in hex.chars() {
+        let value = digit
+            .to_digit(16)
+            .ok_or_else(|| parse_error(line, ParseErrorKind::InvalidValue(hex.to_string())))?;
+        pixels.extend((0..4).rev().map(|bit| ((value >> bit) & 1) as u8));
+    }
+    Ok(pixels)
+}
+
+fn parse_bounding_box(values: &[&str], line: usize) -> Result<BoundingBox, FontError> {
+    Ok(BoundingBox {
+        width: parse_number(values, 0, line)?,
+        height: parse_number(values, 1, line)?,
+        x_offset: parse_number(values, 2, line)?,
+        y_offset: parse_number(values, 3, line)?,
+    })
+}
+
+fn parse_number(values: &[&str], index: usize, line: usize) -> Result<i32, FontError> {
+    let value = values
+        .get(index)
+        .ok_or_else(|| parse_error(line, ParseErrorKind::InvalidValue(values.join(" "))))?;
+    value
+        .parse()
+        .map_err(|_| parse_error(line, ParseErrorKind::InvalidValue(value.to_string())))
+}
+
+fn parse_error(line: usize, kind: ParseErrorKind) -> FontError {
+    FontError::Parse { line, kind }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TINY: &str = include_str!("../tests/fixtures/tiny.bdf");
+    const LATIN1: &[u8] = include_bytes!("../tests/fixtures/latin1.bdf");
+
+    #[test]
+    fn test_parse_fixture() {
+        let font = parse(TINY.as_bytes()).unwrap();
+        assert_eq!(font.height(), 7);
+        assert_eq!(font.ascent(), 6);
+        assert_eq!(font.descent(), 1);
+        assert_eq!(font.supported_characters(), vec!['A', 'g', 'i']);
+
+        // 'A' fills the area above the baseline, leaving the descent row blank
+        let a = font.get_pattern('A').unwrap();
+        assert_eq!(a.width(), 5);
+        assert_eq!(a.to_rows()[0], vec![0, 1, 1, 0, 0]);
+        assert_eq!(a.to_rows()[5], vec![1, 0, 0, 1, 0]);
+        assert_eq!(a.to_rows()[6], vec![0, 0, 0, 0, 0]);
+
+        // 'g' drops below the baseline
+        let g = font.get_pattern('g').unwrap();
+        assert_eq!(g.to_rows()[0], vec![0, 0, 0, 0, 0]);
+        assert_eq!(g.to_rows()[6], vec![0, 1, 1, 0, 0]);
+
+        // 'i' is offset one column from the origin and advances 3
+        let i = font.get_pattern('i').unwrap();
+        assert_eq!(i.width(), 3);
+        assert_eq!(i.to_rows()[1], vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_render_fixture() {
+        let font = parse(TINY.as_bytes()).unwrap();
+        let options = crate::RenderOptions::new().padding(0).letter_spacing(0);
+        let bitmap = font.render("Ai", &options).unwrap();
+        assert_eq!(bitmap.width(), 8);
+        assert_eq!(bitmap.height(), 7);
+    }
+
+    #[test]
+    fn test_non_utf8_properties() {
+        let font = parse(LATIN1).unwrap();
+        assert_eq!(font.supported_characters(), vec!['o']);
+        assert_eq!(font.get_pattern('o').unwrap().width(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let missing_bbx = "STARTFONT 2.1\nSTARTCHAR A\nENCODING 65\nENDCHAR\n";
+        assert_eq!(
+            parse(missing_bbx.as_bytes()).unwrap_err(),
+            FontError::Parse {
+                line: 2,
+                kind: ParseErrorKind::MissingProperty("FONTBOUNDINGBOX".to_string()),
+            }
+        );
+
+        let bad_hex = "FONTBOUNDINGBOX 4 2 0 0\nSTARTCHAR A\nENCODING 65\nBBX 4 2 0 0\nBITMAP\nF0\nZZ\nENDCHAR\n";
+        assert_eq!(
+            parse(bad_hex.as_bytes()).unwrap_err(),
+            FontError::Parse {
+                line: 7,
+                kind: ParseErrorKind::InvalidValue("ZZ".to_string())
+            }
+        );
+
+        let overflowing = "FONT_ASCENT 2147483647\nFONT_DESCENT 1\nSTARTCHAR A\n";
+        assert_eq!(
+            parse(overflowing.as_bytes()).unwrap_err(),
+            FontError::Parse {
+                line: 3,
+                kind: ParseErrorKind::InvalidValue("1".to_string())
+            }
+        );
+
+        let wide = "FONTBOUNDINGBOX 4 2 0 0\nSTARTCHAR A\nENCODING 65\nDWIDTH 2000000000 0\nBBX 4 2 0 0\nBITMAP\nF0\nF0\nENDCHAR\n";
+        assert_eq!(
+            parse(wide.as_bytes()).unwrap_err(),
+            FontError::Parse {
+                line: 9,
+                kind: ParseErrorKind::InvalidValue("2000000000".to_string())
+            }
+        );
+
+        let truncated = "FONTBOUNDINGBOX 4 2 0 0\nSTARTCHAR A\nENCODING 65\n";
+        assert_eq!(
+            parse(truncated.as_bytes()).unwrap_err(),
+            FontError::Parse {
+                line: 3,
+                kind: ParseErrorKind::UnexpectedEnd
+            }
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use crate::{
+    format, netpbm, png, svg, terminal, ImageOptions, NetpbmEncoding, SvgOptions,
+    TerminalOptions, TextFormat,
+};
+
+/// Number of pixels packed into each word of a row
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// How [`Bitmap::blit`] combines source pixels with the pixels beneath them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlitOp {
+    /// Replace the covered pixels with the source
+    Copy,
+    /// Light pixels lit in either bitmap
+    Or,
+    /// Keep only pixels lit in both bitmaps
+    And,
+    /// Flip pixels under lit source pixels
+    Xor,
+}
+
+/// A rendered grid of on/off pixels
+///
+/// Pixels are packed 64 to a word, with each row starting on a new word, so
+/// large banners stay small and [`Bitmap::blit`] works a word at a time.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    /// Words per row
+    stride: usize,
+    /// Bit `x % 64` of word `x / 64` of a row is pixel `x`; bits past the
+    /// width are always 0 so equal bitmaps compare and hash equal
+    words: Vec<u64>,
+}
+
+/// A word with the lowest `bits` bits set
+fn low_bits(bits: usize) -> u64 {
+    if bits >= WORD_BITS {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// The bits of word `index` covering columns `start..end` of a row
+fn span_mask(start: usize, end: usize, index: usize) -> u64 {
+    let word_start = index * WORD_BITS;
+    let from = start.clamp(word_start, word_start + WORD_BITS) - word_start;
+    let to = end.clamp(word_start, word_start + WORD_BITS) - word_start;
+    low_bits(to) & !low_bits(from)
+}
+
+impl Bitmap {
+    /// Create a bitmap of the given size with every pixel off
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// Width of the bitmap in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the bitmap in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns true if the bitmap has no pixels at all
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Get the pixel at `(x, y)`, or `None` if it lies outside the bitmap
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        if x < self.width && y < self.height {
+            Some(self.bit(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Set the pixel at `(x, y)`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` lies outside the bitmap.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) out of bounds for {}x{} bitmap",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        let word = &mut self.words[y * self.stride + x / WORD_BITS];
+        let bit = 1 << (x % WORD_BITS);
+        if value {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+
+    /// Set every pixel in the rectangle at `(x, y)` to `value`
+    ///
+    /// Parts of the rectangle outside the bitmap are ignored.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, value: bool) {
+        let right = x.saturating_add(width).min(self.width);
+        let bottom = y.saturating_add(height).min(self.height);
+        if x >= right {
+            return;
+        }
+        for row in y..bottom {
+            let start = row * self.stride;
+            let words = &mut self.words[start + x / WORD_BITS..start + right.div_ceil(WORD_BITS)];
+            for (index, word) in (x / WORD_BITS..).zip(words) {
+                let mask = span_mask(x, right, index);
+                if value {
+                    *word |= mask;
+                } else {
+                    *word &= !mask;
+                }
+            }
+        }
+    }
+
+    /// Set every pixel to `value`
+    pub fn fill(&mut self, value: bool) {
+        self.fill_rect(0, 0, self.width, self.height, value);
+    }
+
+    /// Combine `source` into this bitmap with its top-left corner at `(x, y)`
+    ///
+    /// Only the pixels covered by `source` are affected, and parts of it that
+    /// fall outside this bitmap are clipped.
+    pub fn blit(&mut self, source: &Bitmap, x: usize, y: usize, op: BlitOp) {
+        let rows = source.height.min(self.height.saturating_sub(y));
+        let first = x / WORD_BITS;
+        let shift = x % WORD_BITS;
+        for row in 0..rows {
+            let source_words = &source.words[row * source.stride..(row + 1) * source.stride];
+            let start = (y + row) * self.stride;
+            for (i, &word) in source_words.iter().enumerate() {
+                let mask = span_mask(0, source.width, i);
+                // A shifted source word straddles at most two destination words
+                let low = (first + i, word << shift, mask << shift);
+                let high = match shift {
+                    0 => (first + i + 1, 0, 0),
+                    _ => (
+                        first + i + 1,
+                        word >> (WORD_BITS - shift),
+                        mask >> (WORD_BITS - shift),
+                    ),
+                };
+                for (index, bits, mask) in [low, high] {
+                    if index >= self.stride {
+                        break;
+                    }
+                    let mask = mask & span_mask(0, self.width, index);
+                    let bits = bits & mask;
+                    let target = &mut self.words[start + index];
+                    *target = match op {
+                        BlitOp::Copy => (*target & !mask) | bits,
+                        BlitOp::Or => *target | bits,
+                        BlitOp::And => *target & (bits | !mask),
+                        BlitOp::Xor => *target ^ bits,
+                    };
+                }
+            }
+        }
+    }
+
+    /// Iterate over the pixels of row `y` from left to right
+    ///
+    /// Yields nothing if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = bool> + '_ {
+        let width = if y < self.height { self.width } else { 0 };
+        (0..width).map(move |x| self.bit(x, y))
+    }
+
+    /// Iterate over the pixels of column `x` from top to bottom
+    ///
+    /// Yields nothing if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = bool> + '_ {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| self.bit(x, y))
+    }
+
+    /// Iterate over all rows from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = bool> + '_> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Iterate over all columns from left to right
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = bool> + '_> + '_ {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Write the bitmap as a PNG image
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] for an empty bitmap, which
+    /// PNG cannot represent, or when the scaled image would be more than
+    /// 2^31 - 1 pixels across.
+    pub fn write_png<W: io::Write>(&self, writer: W, options: &ImageOptions) -> io::Result<()> {
+        png::write_png(self, options, writer)
+    }
+
+    /// Encode the bitmap as a PNG image in memory
+    ///
+    /// See [`Bitmap::write_png`].
+    pub fn to_png(&self, options: &ImageOptions) -> io::Result<Vec<u8>> {
+        let mut png = Vec::new();
+        self.write_png(&mut png, options)?;
+        Ok(png)
+    }
+
+    /// Write the bitmap as a black and white PBM image, P1 or P4
+    ///
+    /// Each bitmap pixel is one image pixel, with lit pixels black. The plain
+    /// encoding is the '1'/'0' text form with a header in front.
+    pub fn write_pbm<W: io::Write>(&self, writer: W, encoding: NetpbmEncoding) -> io::Result<()> {
+        netpbm::write_pbm(self, encoding, writer)
+    }
+
+    /// Write the bitmap as a greyscale PGM image, P2 or P5
+    ///
+    /// Colours are converted to their brightness, after blending over white.
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the scaled image size
+    /// overflows.
+    pub fn write_pgm<W: io::Write>(
+        &self,
+        writer: W,
+        options: &ImageOptions,
+        encoding: NetpbmEncoding,
+    ) -> io::Result<()> {
+        netpbm::write_pgm(self, options, encoding, writer)
+    }
+
+    /// Write the bitmap as a colour PPM image, P3 or P6
+    ///
+    /// Translucent colours are blended over white. Fails like [`Bitmap::write_pgm`].
+    pub fn write_ppm<W: io::Write>(
+        &self,
+        writer: W,
+        options: &ImageOptions,
+        encoding: NetpbmEncoding,
+    ) -> io::Result<()> {
+        netpbm::write_ppm(self, options, encoding, writer)
+    }
+
+    /// Read a plain (P1) or raw (P4) PBM image, lighting its black pixels
+    ///
+    /// Malformed images fail with [`io::ErrorKind::InvalidData`].
+    pub fn from_pbm_reader<R: io::Read>(reader: R) -> io::Result<Self> {
+        netpbm::read_pbm(reader)
+    }
+
+    /// Read a PBM image file
+    ///
+    /// See [`Bitmap::from_pbm_reader`].
+    pub fn from_pbm_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_pbm_reader(File::open(path)?)
+    }
+
+    /// Render the bitmap as an SVG document
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the cell size and gap
+    /// make the image too large to describe.
+    pub fn to_svg(&self, options: &SvgOptions) -> io::Result<String> {
+        svg::to_svg(self, options)
+    }
+
+    /// Write the bitmap as an SVG document
+    ///
+    /// See [`Bitmap::to_svg`].
+    pub fn write_svg<W: io::Write>(&self, mut writer: W, options: &SvgOptions) -> io::Result<()> {
+        writer.write_all(self.to_svg(options)?.as_bytes())
+    }
+
+    /// Write the bitmap as text with custom strings for lit and unlit pixels
+    ///
+    /// `bitmap.format(&TextFormat::digits())` is the same as `bitmap.to_string()`.
+    pub fn format(&self, format: &TextFormat) -> String {
+        let mut out = String::new();
+        let _ = format::write(self, format, &mut out);
+        out
+    }
+
+    /// Draw the bitmap with block or braille characters for display in a terminal
+    ///
+    /// Every line ends with a newline; see [`TerminalOptions`] for colours.
+    pub fn to_terminal(&self, options: &TerminalOptions) -> String {
+        terminal::to_terminal(self, options)
+    }
+
+    /// The pixel at `(x, y)`, which must lie inside the bitmap
+    fn bit(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.stride + x / WORD_BITS] >> (x % WORD_BITS) & 1 == 1
+    }
+}
+
+/// Shows the pixels as rows of '1' and '0' rather than packed words
+impl fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = self
+            .rows()
+            .map(|row| row.map(|pixel| if pixel { '1' } else { '0' }).collect())
+            .collect();
+        f.debug_struct("Bitmap")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("rows", &rows)
+            .finish()
+    }
+}
+
+/// Formats the bitmap as rows of '1' and '0', each terminated by a newline
+impl fmt::Display for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format::write(self, &TextFormat::digits(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_bitmap_is_blank() {
+        let bitmap = Bitmap::new(3, 2);
+        assert_eq!(bitmap.width(), 3);
+        assert_eq!(bitmap.height(), 2);
+        assert!(bitmap.rows().all(|mut row| row.all(|pixel| !pixel)));
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut bitmap = Bitmap::new(3, 2);
+        bitmap.set(2, 1, true);
+
+        assert_eq!(bitmap.get(2, 1), Some(true));
+        assert_eq!(bitmap.get(1, 1), Some(false));
+        assert_eq!(bitmap.get(3, 0), None);
+        assert_eq!(bitmap.get(0, 2), None);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut bitmap = Bitmap::new(3, 2);
+        bitmap.set(0, 0, true);
+        bitmap.set(2, 1, true);
+
+        let rows: Vec<Vec<bool>> = bitmap.rows().map(|row| row.collect()).collect();
+        assert_eq!(rows, vec![vec![true, false, false], vec![false, false, true]]);
+
+        let columns: Vec<Vec<bool>> = bitmap.columns().map(|column| column.collect()).collect();
+        assert_eq!(columns, vec![vec![true, false], vec![false, false], vec![false, true]]);
+
+        assert_eq!(bitmap.row(5).count(), 0);
+        assert_eq!(bitmap.column(5).count(), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut bitmap = Bitmap::new(2, 2);
+        bitmap.set(1, 0, true);
+        assert_eq!(bitmap.to_string(), "01\n00\n");
+        assert_eq!(Bitmap::new(0, 0).to_string(), "");
+    }
+
+    #[test]
+    fn test_fill_rect_spans_words() {
+        let mut bitmap = Bitmap::new(140, 3);
+        bitmap.fill_rect(60, 1, 70, 5, true);
+        assert_eq!(bitmap.row(0).filter(|&pixel| pixel).count(), 0);
+        let lit: Vec<usize> = (0..140).filter(|&x| bitmap.get(x, 1) == Some(true)).collect();
+        assert_eq!(lit, (60..130).collect::<Vec<_>>());
+        assert_eq!(bitmap.column(100).collect::<Vec<_>>(), vec![false, true, true]);
+
+        bitmap.fill(false);
+        assert_eq!(bitmap, Bitmap::new(140, 3));
+    }
+
+    #[test]
+    fn test_blit_ops() {
+        let mut source = Bitmap::new(2, 1);
+        source.set(0, 0, true);
+        let mut target = Bitmap::new(4, 1);
+        target.fill_rect(1, 0, 2, 1, true);
+
+        let blit = |op| {
+            let mut result = target.clone();
+            result.blit(&source, 1, 0, op);
+            result.to_string()
+        };
+        assert_eq!(blit(BlitOp::Copy), "0100\n");
+        assert_eq!(blit(BlitOp::Or), "0110\n");
+        assert_eq!(blit(BlitOp::And), "0100\n");
+        assert_eq!(blit(BlitOp::Xor), "0010\n");
+    }
+
+    #[test]
+    fn test_blit_shifts_across_words_and_clips() {
+        let mut source = Bitmap::new(70, 2);
+        source.fill(true);
+        let mut target = Bitmap::new(100, 2);
+        target.blit(&source, 50, 1, BlitOp::Or);
+
+        assert!(target.row(0).all(|pixel| !pixel));
+        let lit: Vec<usize> = (0..100).filter(|&x| target.get(x, 1) == Some(true)).collect();
+        assert_eq!(lit, (50..100).collect::<Vec<_>>());
+
+        // Clipped pixels must not leak into the padding bits of the row
+        let mut expected = Bitmap::new(100, 2);
+        expected.fill_rect(50, 1, 50, 1, true);
+        assert_eq!(target, expected);
+
+        target.blit(&source, 200, 0, BlitOp::Xor);
+        assert_eq!(target, expected);
+    }
+}
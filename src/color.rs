@@ -0,0 +1,36 @@
+/// An 8-bit-per-channel colour with alpha, used by the image exporters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    /// Red channel
+    pub r: u8,
+    /// Green channel
+    pub g: u8,
+    /// Blue channel
+    pub b: u8,
+    /// Opacity, from 0 (fully transparent) to 255 (fully opaque)
+    pub a: u8,
+}
+
+impl Rgba {
+    /// Opaque black
+    pub const BLACK: Rgba = Rgba::rgb(0, 0, 0);
+    /// Opaque white
+    pub const WHITE: Rgba = Rgba::rgb(255, 255, 255);
+    /// Fully transparent black
+    pub const TRANSPARENT: Rgba = Rgba::new(0, 0, 0, 0);
+
+    /// Create a colour from all four channels
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Create an opaque colour
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r, g, b, 255)
+    }
+
+    /// Returns true if the colour is fully opaque
+    pub fn is_opaque(self) -> bool {
+        self.a == 255
+    }
+}
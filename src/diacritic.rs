@@ -0,0 +1,254 @@
+//! Accented glyphs built from a base glyph and a combining mark
+//!
+//! Marks are drawn one blank row above the highest lit pixel of the base
+//! glyph, or directly below its lowest one. Where the font has spare rows the
+//! composed glyph fits in the usual line height; otherwise the line grows to
+//! make room for the mark.
+
+use std::collections::HashMap;
+
+use crate::layout::GlyphSource;
+use crate::{Bitmap, BlitOp, CharacterPattern};
+
+/// A diacritical mark that can be combined with a base glyph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mark {
+    /// ´ as in é
+    Acute,
+    /// ` as in è
+    Grave,
+    /// ^ as in ê
+    Circumflex,
+    /// ¨ as in ë
+    Umlaut,
+    /// ~ as in ñ
+    Tilde,
+    /// ˚ as in å
+    Ring,
+    /// ˇ as in š
+    Caron,
+    /// ¸ as in ç, drawn below the base glyph
+    Cedilla,
+}
+
+/// Precomposed characters for each mark, paired index by index with their base letters
+const COMPOSED: &[(Mark, &str, &str)] = &[
+    (
+        Mark::Acute,
+        "ÁÉÍÓÚÝáéíóúýĆćĹĺŃńŔŕŚśŹźǴǵ",
+        "AEIOUYaeiouyCcLlNnRrSsZzGg",
+    ),
+    (Mark::Grave, "ÀÈÌÒÙàèìòùǸǹ", "AEIOUaeiouNn"),
+    (
+        Mark::Circumflex,
+        "ÂÊÎÔÛâêîôûĈĉĜĝĤĥĴĵŜŝŴŵŶŷ",
+        "AEIOUaeiouCcGgHhJjSsWwYy",
+    ),
+    (Mark::Umlaut, "ÄËÏÖÜäëïöüŸÿ", "AEIOUaeiouYy"),
+    (Mark::Tilde, "ÃÑÕãñõĨĩŨũ", "ANOanoIiUu"),
+    (Mark::Ring, "ÅåŮů", "AaUu"),
+    (
+        Mark::Caron,
+        "ČčĎďĚěŇňŘřŠšŤťŽžǍǎǏǐǑǒǓǔ",
+        "CcDdEeNnRrSsTtZzAaIiOoUu",
+    ),
+    (Mark::Cedilla, "ÇçŞşŢţĢģĶķĻļŅņŖŗ", "CcSsTtGgKkLlNnRr"),
+];
+
+impl Mark {
+    /// Split a precomposed character into its base letter and mark
+    ///
+    /// ```
+    /// use text_to_input::Mark;
+    ///
+    /// assert_eq!(Mark::decompose('é'), Some(('e', Mark::Acute)));
+    /// assert_eq!(Mark::decompose('e'), None);
+    /// ```
+    pub fn decompose(ch: char) -> Option<(char, Mark)> {
+        COMPOSED.iter().find_map(|&(mark, composed, bases)| {
+            let index = composed.chars().position(|c| c == ch)?;
+            bases.chars().nth(index).map(|base| (base, mark))
+        })
+    }
+
+    /// The pixels of the mark on its own
+    pub fn pattern(self) -> CharacterPattern {
+        let rows: &[&[u8]] = match self {
+            Mark::Acute => &[&[0, 1], &[1, 0]],
+            Mark::Grave => &[&[1, 0], &[0, 1]],
+            Mark::Circumflex => &[&[0, 1, 0], &[1, 0, 1]],
+            Mark::Umlaut => &[&[1, 0, 1]],
+            Mark::Tilde => &[&[0, 1, 0, 1], &[1, 0, 1, 0]],
+            Mark::Ring => &[&[0, 1, 0], &[1, 0, 1], &[0, 1, 0]],
+            Mark::Caron => &[&[1, 0, 1], &[0, 1, 0]],
+            Mark::Cedilla => &[&[0, 1], &[1, 1]],
+        };
+        CharacterPattern::new(rows)
+    }
+
+    /// Returns true if the mark hangs below the base glyph instead of above it
+    pub fn is_below(self) -> bool {
+        self == Mark::Cedilla
+    }
+}
+
+/// Draw `mark` above or below `base`, growing the pattern where needed
+///
+/// Both are centered horizontally on the wider of the two.
+pub(crate) fn compose(base: &CharacterPattern, mark: Mark) -> CharacterPattern {
+    let mark_pattern = mark.pattern();
+    let width = base.width().max(mark_pattern.width());
+    let lit_rows: Vec<usize> = base
+        .bitmap()
+        .rows()
+        .enumerate()
+        .filter_map(|(y, mut row)| row.any(|pixel| pixel).then_some(y))
+        .collect();
+
+    let (base_top, mark_top, height, baseline) = if mark.is_below() {
+        let bottom = lit_rows.last().map_or(base.baseline(), |&y| y + 1);
+        let height = base.height().max(bottom + mark_pattern.height());
+        (0, bottom, height, base.baseline())
+    } else {
+        // Leave a blank row between the mark and the top of the glyph
+        let top = lit_rows.first().copied().unwrap_or(base.baseline());
+        let extra = (mark_pattern.height() + 1).saturating_sub(top);
+        let mark_top = extra + top - 1 - mark_pattern.height();
+        (
+            extra,
+            mark_top,
+            base.height() + extra,
+            base.baseline() + extra,
+        )
+    };
+
+    let mut bitmap = Bitmap::new(width, height);
+    for (pattern, top) in [(base, base_top), (&mark_pattern, mark_top)] {
+        let left = (width - pattern.width()) / 2;
+        bitmap.blit(pattern.bitmap(), left, top, BlitOp::Or);
+    }
+    CharacterPattern::from_bitmap(bitmap, baseline)
+}
+
+/// Build the accented glyph for `ch` from the font's glyph for its base letter
+pub(crate) fn compose_char<G: GlyphSource + ?Sized>(
+    font: &G,
+    ch: char,
+) -> Option<CharacterPattern> {
+    let (base, mark) = Mark::decompose(ch)?;
+    font.glyph(base).map(|pattern| compose(pattern, mark))
+}
+
+/// A font plus accented glyphs composed for the characters of one text
+///
+/// The line grows to fit the tallest mark actually used, so text without
+/// accents renders exactly as it does with the font alone.
+pub(crate) struct Composed<'a, G: ?Sized> {
+    font: &'a G,
+    accented: HashMap<char, CharacterPattern>,
+    ascent: usize,
+    descent: usize,
+}
+
+impl<'a, G: GlyphSource + ?Sized> Composed<'a, G> {
+    /// Compose glyphs for the characters of `text` the font lacks, if `enabled`
+    pub(crate) fn new(font: &'a G, text: &str, enabled: bool) -> Self {
+        let mut accented = HashMap::new();
+        if enabled {
+            for ch in text.chars() {
+                if font.glyph(ch).is_some() || accented.contains_key(&ch) {
+                    continue;
+                }
+                if let Some(pattern) = compose_char(font, ch) {
+                    accented.insert(ch, pattern);
+                }
+            }
+        }
+
+        let ascent = accented
+            .values()
+            .map(CharacterPattern::baseline)
+            .fold(font.ascent(), usize::max);
+        let descent = accented
+            .values()
+            .map(CharacterPattern::descent)
+            .fold(font.descent(), usize::max);
+        Self {
+            font,
+            accented,
+            ascent,
+            descent,
+        }
+    }
+}
+
+impl<G: GlyphSource + ?Sized> GlyphSource for Composed<'_, G> {
+    fn glyph(&self, ch: char) -> Option<&CharacterPattern> {
+        self.font.glyph(ch).or_else(|| self.accented.get(&ch))
+    }
+
+    fn ascent(&self) -> usize {
+        self.ascent
+    }
+
+    fn descent(&self) -> usize {
+        self.descent
+    }
+
+    fn kerning(&self, left: char, right: char) -> isize {
+        self.font.kerning(left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PixelFont;
+
+    #[test]
+    fn test_composed_tables_line_up() {
+        for (mark, composed, bases) in COMPOSED {
+            assert_eq!(
+                composed.chars().count(),
+                bases.chars().count(),
+                "{:?}",
+                mark
+            );
+        }
+        assert_eq!(Mark::decompose('Ç'), Some(('C', Mark::Cedilla)));
+        assert_eq!(Mark::decompose('ǔ'), Some(('u', Mark::Caron)));
+    }
+
+    #[test]
+    fn test_mark_above_uses_spare_rows() {
+        let base =
+            CharacterPattern::new(&[&[0, 0, 0], &[0, 0, 0], &[0, 0, 0], &[1, 1, 1], &[1, 0, 1]]);
+        let composed = compose(&base, Mark::Umlaut);
+        assert_eq!(composed.height(), 5);
+        assert_eq!(composed.baseline(), 5);
+        let rows = composed.to_rows();
+        assert_eq!(rows[1], vec![1, 0, 1]);
+        assert_eq!(rows[2], vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_mark_grows_glyph() {
+        // 'e' has one blank row on top, the acute needs two rows plus a gap
+        let e = PixelFont::new().get_pattern('e').unwrap().clone();
+        let composed = compose(&e, Mark::Acute);
+        assert_eq!(composed.height(), 7);
+        assert_eq!(composed.baseline(), 7);
+        let rows = composed.to_rows();
+        assert_eq!(rows[0], vec![0, 1, 0]);
+        assert_eq!(rows[1], vec![1, 0, 0]);
+        assert_eq!(&rows[2..], &e.to_rows()[..]);
+
+        let cedilla = compose(
+            &PixelFont::new().get_pattern('c').unwrap().clone(),
+            Mark::Cedilla,
+        );
+        assert_eq!(cedilla.height(), 7);
+        assert_eq!(cedilla.baseline(), 5);
+        assert_eq!(cedilla.descent(), 2);
+    }
+}
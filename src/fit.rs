@@ -0,0 +1,121 @@
+use crate::{Bitmap, PixelArtError, PixelFont, RenderOptions};
+
+/// Text rendered to fit a target width, with the choices that made it fit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FittedRender {
+    /// The rendered text
+    pub bitmap: Bitmap,
+    /// Index of the font used in the list of fonts tried
+    pub font_index: usize,
+    /// Letter spacing the text was rendered with
+    pub letter_spacing: isize,
+    /// Word spacing the text was rendered with
+    pub word_spacing: usize,
+}
+
+/// Letter and word spacings to try, loosest first
+///
+/// Letter spacing shrinks to 0 before word spacing shrinks to 1. Spacings
+/// already tighter than that are kept as they are.
+fn spacings(options: &RenderOptions) -> impl Iterator<Item = (isize, usize)> {
+    let (letter, word) = (options.letter_spacing, options.word_spacing);
+    let tightest_letter = letter.min(0);
+    let letters = (tightest_letter..=letter).rev().map(move |l| (l, word));
+    let words = (word.min(1)..word).rev().map(move |w| (tightest_letter, w));
+    letters.chain(words)
+}
+
+/// Render with the first font and spacing that make the text fit in `width`
+pub(crate) fn fit(
+    fonts: &[PixelFont],
+    text: &str,
+    width: usize,
+    options: &RenderOptions,
+) -> Result<FittedRender, PixelArtError> {
+    let mut narrowest: Option<usize> = None;
+    let mut unsupported = None;
+    for (font_index, font) in fonts.iter().enumerate() {
+        for (letter_spacing, word_spacing) in spacings(options) {
+            let attempt = options
+                .clone()
+                .letter_spacing(letter_spacing)
+                .word_spacing(word_spacing);
+            match font.measure(text, &attempt) {
+                Ok(measurement) if measurement.width <= width => {
+                    return font.render(text, &attempt).map(|bitmap| FittedRender {
+                        bitmap,
+                        font_index,
+                        letter_spacing,
+                        word_spacing,
+                    });
+                }
+                Ok(measurement) => {
+                    let width = measurement.width;
+                    narrowest = Some(narrowest.map_or(width, |narrowest| narrowest.min(width)));
+                }
+                // A font that cannot draw the text is skipped in favour of the next one
+                Err(error @ PixelArtError::UnsupportedCharacter(_)) => {
+                    unsupported.get_or_insert(error);
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    match (narrowest, unsupported) {
+        (None, Some(error)) => Err(error),
+        (narrowest, _) => Err(PixelArtError::DoesNotFit {
+            width: narrowest.unwrap_or(0),
+            target: width,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spacing_order() {
+        let options = RenderOptions::new().letter_spacing(2).word_spacing(3);
+        let tried: Vec<_> = spacings(&options).collect();
+        assert_eq!(tried, vec![(2, 3), (1, 3), (0, 3), (0, 2), (0, 1)]);
+    }
+
+    #[test]
+    fn test_tightens_spacing_until_it_fits() {
+        let font = PixelFont::new();
+        let options = RenderOptions::new();
+        let loose = font.measure("Hi there", &options).unwrap().width;
+
+        let fitted = fit(std::slice::from_ref(&font), "Hi there", loose - 1, &options).unwrap();
+        assert_eq!((fitted.letter_spacing, fitted.word_spacing), (0, 2));
+        assert!(fitted.bitmap.width() < loose);
+
+        let untouched = fit(&[font], "Hi there", loose, &options).unwrap();
+        assert_eq!((untouched.letter_spacing, untouched.word_spacing), (1, 2));
+    }
+
+    #[test]
+    fn test_falls_back_to_other_fonts() {
+        let narrow: PixelFont = "char H\n#\n#\n#\n#\n#\n\nchar i\n#\n.\n#\n#\n#\n"
+            .parse()
+            .unwrap();
+        let fitted = fit(&[PixelFont::new(), narrow], "Hi", 5, &RenderOptions::new()).unwrap();
+        assert_eq!(fitted.font_index, 1);
+        assert_eq!(fitted.bitmap.width(), 5);
+
+        assert_eq!(
+            fit(&[PixelFont::new()], "Hi", 5, &RenderOptions::new()),
+            Err(PixelArtError::DoesNotFit {
+                width: 7,
+                target: 5
+            })
+        );
+        assert_eq!(
+            fit(&[PixelFont::empty()], "Hi", 5, &RenderOptions::new()),
+            Err(PixelArtError::UnsupportedCharacter('H'))
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Plain-text glyph definition format
+//!
+//! A font file is a list of glyphs. Each glyph starts with a header line
+//! naming the character, followed by one line per pixel row using `#` or
+//! `1` for lit pixels and `.` or `0` for blank ones:
+//!
+//! ```text
+//! // Comments start with two slashes
+//! char A
+//! .##.
+//! #..#
+//! ####
+//! #..#
+//! #..#
+//!
+//! char U+0021
+//! #
+//! #
+//! #
+//! .
+//! #
+//! ```
+//!
+//! The character after `char` is taken literally; `U+XXXX` names a character
+//! by code point, which is how spaces and other invisible characters are
+//! written. Blank lines between glyphs are optional.
+//!
+//! Glyphs are 5 rows tall and sit on the baseline unless the file starts with
+//! `height N` (rows per glyph) and `baseline N` (rows above the baseline,
+//! defaulting to the height) lines. A file without any glyphs still describes
+//! a font of that height, so `height 0` is allowed for an empty font. Rows
+//! below the baseline form descenders:
+//!
+//! ```text
+//! height 7
+//! baseline 5
+//!
+//! char g
+//! ....
+//! .###
+//! #..#
+//! #..#
+//! .###
+//! ...#
+//! ###.
+//! ```
+//!
+//! Lines of the form `kern L R N` adjust the spacing between the characters
+//! `L` and `R` (single characters or `U+XXXX`) by `N` columns, usually a
+//! negative number to tighten pairs such as `kern A V -1`. They may appear
+//! anywhere outside a glyph's rows.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::{self, BufRead};
+
+use crate::{CharacterPattern, FontError, ParseErrorKind, PixelFont};
+
+/// Number of pixel rows every glyph has unless the file says otherwise
+const DEFAULT_HEIGHT: usize = 5;
+
+/// A glyph being collected while parsing
+struct PendingGlyph {
+    ch: char,
+    line: usize,
+    rows: Vec<Vec<u8>>,
+}
+
+/// Parse a font from its plain-text definition
+pub(crate) fn parse<R: BufRead>(reader: R) -> Result<PixelFont, FontError> {
+    let mut height = DEFAULT_HEIGHT;
+    let mut baseline = None;
+    let mut font: Option<PixelFont> = None;
+    let mut pending: Option<PendingGlyph> = None;
+    let mut kerning = Vec::new();
+    let mut kerned = HashSet::new();
+    let mut line_number = 0;
+
+    for line in reader.lines() {
+        line_number += 1;
+        let line = line.map_err(|e| FontError::Io(e.to_string()))?;
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if let Some(spec) = trimmed
+            .strip_prefix("kern")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+        {
+            let (left, right, columns) = parse_kerning(spec, line_number)?;
+            if !kerned.insert((left, right)) {
+                return Err(parse_error(
+                    line_number,
+                    ParseErrorKind::DuplicateKerning(left, right),
+                ));
+            }
+            kerning.push((left, right, columns));
+            continue;
+        }
+
+        if let Some((name, value)) = parse_directive(trimmed) {
+            if font.is_some() {
+                return Err(parse_error(
+                    line_number,
+                    ParseErrorKind::MisplacedDirective(name.to_string()),
+                ));
+            }
+            let value = value.parse::<usize>().map_err(|_| {
+                parse_error(line_number, ParseErrorKind::InvalidValue(value.to_string()))
+            })?;
+            match name {
+                "height" => height = value,
+                _ => baseline = Some(value),
+            }
+            continue;
+        }
+
+        if let Some(spec) = trimmed
+            .strip_prefix("char")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+        {
+            let font = match font.as_mut() {
+                Some(font) => font,
+                None => font.insert(font_with_metrics(height, baseline, line_number)?),
+            };
+            if let Some(glyph) = pending.take() {
+                finish_glyph(font, glyph)?;
+            }
+            let spec = spec.trim();
+            let ch = parse_char_spec(spec).ok_or_else(|| {
+                parse_error(
+                    line_number,
+                    ParseErrorKind::InvalidCharacter(spec.to_string()),
+                )
+            })?;
+            if font.get_pattern(ch).is_some() {
+                return Err(parse_error(line_number, ParseErrorKind::DuplicateGlyph(ch)));
+            }
+            pending = Some(PendingGlyph {
+                ch,
+                line: line_number,
+                rows: Vec::with_capacity(height),
+            });
+            continue;
+        }
+
+        let glyph = pending
+            .as_mut()
+            .ok_or_else(|| parse_error(line_number, ParseErrorKind::MissingHeader))?;
+        let row = trimmed
+            .chars()
+            .map(|ch| match ch {
+                '#' | '1' => Ok(1),
+                '.' | '0' => Ok(0),
+                other => Err(parse_error(
+                    line_number,
+                    ParseErrorKind::InvalidPixel(other),
+                )),
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        if let Some(first) = glyph.rows.first() {
+            if first.len() != row.len() {
+                return Err(parse_error(
+                    line_number,
+                    ParseErrorKind::RaggedRow {
+                        expected: first.len(),
+                        actual: row.len(),
+                    },
+                ));
+            }
+        }
+        glyph.rows.push(row);
+    }
+
+    // A file without glyphs still gets the height and baseline it declares
+    let mut font = match font {
+        Some(font) => font,
+        None => font_with_metrics(height, baseline, line_number)?,
+    };
+    if let Some(glyph) = pending {
+        finish_glyph(&mut font, glyph)?;
+    }
+    for (left, right, columns) in kerning {
+        font.set_kerning(left, right, columns);
+    }
+    Ok(font)
+}
+
+/// Create the font described by the `height` and `baseline` directives
+fn font_with_metrics(
+    height: usize,
+    baseline: Option<usize>,
+    line: usize,
+) -> Result<PixelFont, FontError> {
+    let ascent = baseline.unwrap_or(height);
+    if ascent > height {
+        return Err(parse_error(
+            line,
+            ParseErrorKind::InvalidValue(ascent.to_string()),
+        ));
+    }
+    Ok(PixelFont::with_metrics(ascent, height - ascent))
+}
+
+/// Parse the `L R N` part of a `kern` line
+fn parse_kerning(spec: &str, line: usize) -> Result<(char, char, isize), FontError> {
+    let fields: Vec<&str> = spec.split_whitespace().collect();
+    let [left, right, columns] = fields[..] else {
+        return Err(parse_error(
+            line,
+            ParseErrorKind::InvalidValue(spec.trim().to_string()),
+        ));
+    };
+    let parse_char = |spec: &str| {
+        parse_char_spec(spec)
+            .ok_or_else(|| parse_error(line, ParseErrorKind::InvalidCharacter(spec.to_string())))
+    };
+    let columns = columns
+        .parse()
+        .map_err(|_| parse_error(line, ParseErrorKind::InvalidValue(columns.to_string())))?;
+    Ok((parse_char(left)?, parse_char(right)?, columns))
+}
+
+/// Recognise a `height N` or `baseline N` line
+fn parse_directive(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.split_once(char::is_whitespace)?;
+    matches!(name, "height" | "baseline").then_some((name, value.trim()))
+}
+
+/// Check a completed glyph and add it to the font
+fn finish_glyph(font: &mut PixelFont, glyph: PendingGlyph) -> Result<(), FontError> {
+    if glyph.rows.len() != font.height() {
+        return Err(parse_error(
+            glyph.line,
+            ParseErrorKind::WrongRowCount {
+                expected: font.height(),
+                actual: glyph.rows.len(),
+            },
+        ));
+    }
+    let rows: Vec<&[u8]> = glyph.rows.iter().map(Vec::as_slice).collect();
+    let pattern = CharacterPattern::try_new(&rows)
+        .map_err(|error| parse_error(glyph.line, ParseErrorKind::InvalidGlyph(error)))?
+        .with_baseline(font.ascent());
+    font.insert(glyph.ch, pattern);
+    Ok(())
+}
+
+/// Parse the character named in a glyph header
+fn parse_char_spec(spec: &str) -> Option<char> {
+    let mut chars = spec.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => {
+            let hex = spec
+                .strip_prefix("U+")
+                .or_else(|| spec.strip_prefix("u+"))?;
+            u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+        }
+    }
+}
+
+fn parse_error(line: usize, kind: ParseErrorKind) -> FontError {
+    FontError::Parse { line, kind }
+}
+
+/// Write a font in the plain-text format, glyphs sorted by character
+pub(crate) fn write<W: io::Write>(font: &PixelFont, mut writer: W) -> io::Result<()> {
+    writer.write_all(to_string(font).as_bytes())
+}
+
+/// Format a font in the plain-text format, glyphs sorted by character
+pub(crate) fn to_string(font: &PixelFont) -> String {
+    let mut out = String::new();
+    if font.height() != DEFAULT_HEIGHT || font.descent() > 0 {
+        let _ = writeln!(out, "height {}", font.height());
+        let _ = writeln!(out, "baseline {}", font.ascent());
+    }
+    for ch in font.supported_characters() {
+        let Some(pattern) = font.get_pattern(ch) else {
+            continue;
+        };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "char {}", char_spec(ch));
+        // Glyphs are written as full rows of the font so they share a baseline
+        for y in 0..font.height() {
+            match font.cell_row(pattern, y) {
+                Some(row) => out.extend(row.map(|pixel| if pixel { '#' } else { '.' })),
+                None => out.extend(std::iter::repeat_n('.', pattern.width())),
+            }
+            out.push('\n');
+        }
+    }
+
+    let pairs = font.kerning_pairs();
+    if !pairs.is_empty() && !out.is_empty() {
+        out.push('\n');
+    }
+    for ((left, right), columns) in pairs {
+        let _ = writeln!(
+            out,
+            "kern {} {} {}",
+            char_spec(left),
+            char_spec(right),
+            columns
+        );
+    }
+    out
+}
+
+/// Name a character the way glyph headers and `kern` lines expect
+fn char_spec(ch: char) -> String {
+    if ch.is_whitespace() || ch.is_control() {
+        format!("U+{:04X}", ch as u32)
+    } else {
+        ch.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(text: &str) -> Result<PixelFont, FontError> {
+        parse(text.as_bytes())
+    }
+
+    #[test]
+    fn test_parse_glyphs() {
+        let font = parse_str(
+            "// tiny font\nchar A\n.##.\n#..#\n####\n#..#\n#..#\n\nchar U+0021\n1\n1\n1\n0\n1\n",
+        )
+        .unwrap();
+
+        assert_eq!(font.supported_characters(), vec!['!', 'A']);
+        assert_eq!(font.get_pattern('A').unwrap().width(), 4);
+        assert_eq!(font.get_pattern('!').unwrap().to_rows()[3], vec![0]);
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        assert_eq!(
+            parse_str("#.#\n").unwrap_err(),
+            FontError::Parse {
+                line: 1,
+                kind: ParseErrorKind::MissingHeader
+            }
+        );
+        assert_eq!(
+            parse_str("char A\n##\n#x\n").unwrap_err(),
+            FontError::Parse {
+                line: 3,
+                kind: ParseErrorKind::InvalidPixel('x')
+            }
+        );
+        assert_eq!(
+            parse_str("char A\n##\n###\n").unwrap_err(),
+            FontError::Parse {
+                line: 3,
+                kind: ParseErrorKind::RaggedRow {
+                    expected: 2,
+                    actual: 3
+                }
+            }
+        );
+        assert_eq!(
+            parse_str("char A\n#\n#\n\nchar B\n#\n").unwrap_err(),
+            FontError::Parse {
+                line: 1,
+                kind: ParseErrorKind::WrongRowCount {
+                    expected: 5,
+                    actual: 2
+                }
+            }
+        );
+        assert_eq!(
+            parse_str("char AB\n").unwrap_err(),
+            FontError::Parse {
+                line: 1,
+                kind: ParseErrorKind::InvalidCharacter("AB".to_string())
+            }
+        );
+        assert_eq!(
+            parse_str("char A\n#\n#\n#\n#\n#\nchar A\n").unwrap_err(),
+            FontError::Parse {
+                line: 7,
+                kind: ParseErrorKind::DuplicateGlyph('A')
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_height_and_baseline() {
+        let font = parse_str("height 3\nbaseline 2\nchar q\n##\n##\n.#\n").unwrap();
+        assert_eq!(font.ascent(), 2);
+        assert_eq!(font.descent(), 1);
+        assert_eq!(font.get_pattern('q').unwrap().descent(), 1);
+
+        assert_eq!(
+            parse_str("height 3\nchar q\n#\n#\n#\n#\n#\n").unwrap_err(),
+            FontError::Parse {
+                line: 2,
+                kind: ParseErrorKind::WrongRowCount {
+                    expected: 3,
+                    actual: 5
+                }
+            }
+        );
+        assert_eq!(
+            parse_str("char q\n#\n#\n#\n#\n#\nheight 3\n").unwrap_err(),
+            FontError::Parse {
+                line: 7,
+                kind: ParseErrorKind::MisplacedDirective("height".to_string())
+            }
+        );
+        assert_eq!(
+            parse_str("height 3\nbaseline 4\nchar q\n").unwrap_err(),
+            FontError::Parse {
+                line: 3,
+                kind: ParseErrorKind::InvalidValue("4".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_taller_font_round_trips() {
+        let mut font = PixelFont::with_metrics(4, 0);
+        font.insert('o', CharacterPattern::new(&[&[1, 1], &[1, 1]]));
+        font.insert(
+            'q',
+            CharacterPattern::new(&[&[1, 1], &[1, 1], &[0, 1]]).with_baseline(2),
+        );
+
+        let text = to_string(&font);
+        assert!(text.starts_with("height 5\nbaseline 4\n"));
+
+        // Shorter glyphs come back padded to the full line height
+        let parsed = parse_str(&text).unwrap();
+        assert_eq!(parsed.height(), 5);
+        assert_eq!(
+            parsed.get_pattern('o').unwrap().to_rows(),
+            vec![vec![0, 0], vec![0, 0], vec![1, 1], vec![1, 1], vec![0, 0]]
+        );
+        assert_eq!(to_string(&parsed), text);
+    }
+
+    #[test]
+    fn test_fonts_without_glyphs_round_trip() {
+        let empty = PixelFont::empty();
+        assert_eq!(to_string(&empty), "height 0\nbaseline 0\n");
+        assert_eq!(parse_str(&to_string(&empty)).unwrap(), empty);
+
+        // The declared metrics are kept even though no glyph uses them
+        let tall = parse_str("height 7\n").unwrap();
+        assert_eq!((tall.ascent(), tall.descent()), (7, 0));
+        let descending = PixelFont::with_metrics(6, 2);
+        assert_eq!(parse_str(&to_string(&descending)).unwrap(), descending);
+        assert_eq!(parse_str("").unwrap(), PixelFont::with_metrics(5, 0));
+    }
+
+    #[test]
+    fn test_kerning() {
+        let text = "kern A V -1\nchar A\n#\n#\n#\n#\n#\nkern U+0020 A 2\n\nchar V\n#\n#\n#\n#\n#\n";
+        let font = parse_str(text).unwrap();
+        assert_eq!(font.kerning('A', 'V'), -1);
+        assert_eq!(font.kerning(' ', 'A'), 2);
+        assert_eq!(font.kerning('V', 'A'), 0);
+        assert_eq!(font.get_pattern('A').unwrap().height(), 5);
+
+        let written = to_string(&font);
+        assert!(written.ends_with("\nkern U+0020 A 2\nkern A V -1\n"));
+        assert_eq!(parse_str(&written).unwrap(), font);
+
+        assert_eq!(
+            parse_str("kern A V -1\nkern A V 1\n").unwrap_err(),
+            FontError::Parse {
+                line: 2,
+                kind: ParseErrorKind::DuplicateKerning('A', 'V')
+            }
+        );
+        assert_eq!(
+            parse_str("kern AV -1\n").unwrap_err(),
+            FontError::Parse {
+                line: 1,
+                kind: ParseErrorKind::InvalidValue("AV -1".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_builtin_font_round_trips() {
+        let font = PixelFont::new();
+        let text = to_string(&font);
+        assert_eq!(parse_str(&text).unwrap(), font);
+    }
+}
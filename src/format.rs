@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::Bitmap;
+
+/// The strings used to write a bitmap as text
+///
+/// Every lit pixel is written as the `on` string and every unlit pixel as the
+/// `off` string, with the line separator after each row, including the last.
+/// Strings may be any length, such as `"██"` for square pixels or emoji
+/// shortcodes for chat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFormat {
+    pub(crate) on: String,
+    pub(crate) off: String,
+    pub(crate) line_separator: String,
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self::digits()
+    }
+}
+
+impl TextFormat {
+    /// Create a format writing `on` and `off` for each pixel, one row per line
+    pub fn new(on: impl Into<String>, off: impl Into<String>) -> Self {
+        Self {
+            on: on.into(),
+            off: off.into(),
+            line_separator: "\n".to_string(),
+        }
+    }
+
+    /// Set what is written after each row, `"\n"` by default
+    pub fn line_separator(mut self, separator: impl Into<String>) -> Self {
+        self.line_separator = separator.into();
+        self
+    }
+
+    /// '1' and '0', the classic output of [`crate::text_to_pixel_art`]
+    pub fn digits() -> Self {
+        Self::new("1", "0")
+    }
+
+    /// Two full blocks per lit pixel and two spaces per unlit one
+    pub fn blocks() -> Self {
+        Self::new("██", "  ")
+    }
+
+    /// '#' on a blank background
+    pub fn hash() -> Self {
+        Self::new("#", " ")
+    }
+
+    /// Black and white square emoji, which chat apps show as a pixel grid
+    pub fn emoji() -> Self {
+        Self::new("⬛", "⬜")
+    }
+
+    /// Look up a preset by name: "digits", "blocks", "hash" or "emoji"
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "digits" => Some(Self::digits()),
+            "blocks" => Some(Self::blocks()),
+            "hash" => Some(Self::hash()),
+            "emoji" => Some(Self::emoji()),
+            _ => None,
+        }
+    }
+}
+
+/// Write `bitmap` to `out` in `format`
+pub(crate) fn write<W: fmt::Write>(
+    bitmap: &Bitmap,
+    format: &TextFormat,
+    mut out: W,
+) -> fmt::Result {
+    for row in bitmap.rows() {
+        for pixel in row {
+            out.write_str(if pixel { &format.on } else { &format.off })?;
+        }
+        out.write_str(&format.line_separator)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: &TextFormat) -> String {
+        let mut bitmap = Bitmap::new(2, 2);
+        bitmap.set(0, 0, true);
+        bitmap.set(1, 1, true);
+        let mut out = String::new();
+        write(&bitmap, format, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(format(&TextFormat::default()), "10\n01\n");
+        assert_eq!(format(&TextFormat::blocks()), "██  \n  ██\n");
+        assert_eq!(format(&TextFormat::hash()), "# \n #\n");
+        assert_eq!(format(&TextFormat::emoji()), "⬛⬜\n⬜⬛\n");
+        assert_eq!(TextFormat::preset("hash"), Some(TextFormat::hash()));
+        assert_eq!(TextFormat::preset("stars"), None);
+    }
+
+    #[test]
+    fn test_custom_strings() {
+        let slack = TextFormat::new(":black_square:", ":white_square:").line_separator("\r\n");
+        assert_eq!(
+            format(&slack),
+            ":black_square::white_square:\r\n:white_square::black_square:\r\n"
+        );
+        assert_eq!(format(&TextFormat::new("", "").line_separator("|")), "||");
+    }
+}
@@ -0,0 +1,162 @@
+use std::io;
+
+use crate::{Bitmap, Rgba};
+
+/// How a bitmap is turned into an image by the raster exporters
+///
+/// Each pixel of the bitmap becomes a `scale` by `scale` cell, optionally
+/// separated from its neighbours by grid lines `gap` pixels wide. Built with
+/// chained setters starting from [`ImageOptions::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub(crate) scale: usize,
+    pub(crate) gap: usize,
+    pub(crate) on: Rgba,
+    pub(crate) off: Rgba,
+    pub(crate) gap_color: Option<Rgba>,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            gap: 0,
+            on: Rgba::BLACK,
+            off: Rgba::WHITE,
+            gap_color: None,
+        }
+    }
+}
+
+impl ImageOptions {
+    /// Create options for black pixels on white, one image pixel per bitmap pixel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw each bitmap pixel as a `scale` by `scale` block; 0 is treated as 1
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Separate neighbouring cells with grid lines `gap` pixels wide
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Set the colour of lit pixels
+    pub fn on_color(mut self, color: Rgba) -> Self {
+        self.on = color;
+        self
+    }
+
+    /// Set the colour of unlit pixels, which may be transparent
+    pub fn off_color(mut self, color: Rgba) -> Self {
+        self.off = color;
+        self
+    }
+
+    /// Set the colour of the grid lines; by default they match unlit pixels
+    pub fn gap_color(mut self, color: Rgba) -> Self {
+        self.gap_color = Some(color);
+        self
+    }
+
+    /// The colours of unlit pixels, lit pixels and grid lines, indexed by [`Pixel`]
+    pub(crate) fn palette(&self) -> [Rgba; 3] {
+        [self.off, self.on, self.gap_color.unwrap_or(self.off)]
+    }
+
+    /// Width and height of the image produced for `bitmap`
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] when the image would have
+    /// more pixels than fit in memory, so writers never overflow.
+    pub(crate) fn image_size(&self, bitmap: &Bitmap) -> io::Result<(usize, usize)> {
+        let size = |cells: usize| {
+            let gaps = cells.saturating_sub(1).checked_mul(self.gap)?;
+            cells.checked_mul(self.scale)?.checked_add(gaps)
+        };
+        match (size(bitmap.width()), size(bitmap.height())) {
+            (Some(width), Some(height)) if width.checked_mul(height).is_some() => {
+                Ok((width, height))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image is too large for the scale and gap",
+            )),
+        }
+    }
+}
+
+/// What an image pixel shows, in palette order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Pixel {
+    Off = 0,
+    On = 1,
+    Gap = 2,
+}
+
+/// Which cell an image coordinate falls in, or `None` for a grid line
+fn cell(position: usize, options: &ImageOptions) -> Option<usize> {
+    // The gap may be huge when there is only one cell and so no grid lines
+    let pitch = options.scale.saturating_add(options.gap);
+    (position % pitch < options.scale).then_some(position / pitch)
+}
+
+/// The pixels of each image row, top to bottom, for an image of the size
+/// returned by [`ImageOptions::image_size`]
+pub(crate) fn rows<'a>(
+    bitmap: &'a Bitmap,
+    options: &'a ImageOptions,
+    (width, height): (usize, usize),
+) -> impl Iterator<Item = Vec<Pixel>> + 'a {
+    (0..height).map(move |y| match cell(y, options) {
+        None => vec![Pixel::Gap; width],
+        Some(row) => (0..width)
+            .map(|x| match cell(x, options) {
+                None => Pixel::Gap,
+                Some(column) if bitmap.get(column, row) == Some(true) => Pixel::On,
+                Some(_) => Pixel::Off,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled_cells_and_gaps() {
+        let mut bitmap = Bitmap::new(2, 2);
+        bitmap.set(0, 0, true);
+        let options = ImageOptions::new().scale(2).gap(1);
+        let size = options.image_size(&bitmap).unwrap();
+        assert_eq!(size, (5, 5));
+
+        let rows: Vec<String> = rows(&bitmap, &options, size)
+            .map(|row| {
+                row.into_iter()
+                    .map(|pixel| match pixel {
+                        Pixel::On => '#',
+                        Pixel::Off => '.',
+                        Pixel::Gap => '+',
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows, vec!["##+..", "##+..", "+++++", "..+..", "..+.."]);
+    }
+
+    #[test]
+    fn test_palette_defaults() {
+        let options = ImageOptions::new().off_color(Rgba::TRANSPARENT);
+        assert_eq!(
+            options.palette(),
+            [Rgba::TRANSPARENT, Rgba::BLACK, Rgba::TRANSPARENT]
+        );
+        assert_eq!(ImageOptions::new().scale(0).scale, 1);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{Alignment, Bitmap, BlitOp, CharacterPattern, PixelArtError, PixelFont, RenderOptions};
+
+/// Where layout looks up glyphs and line metrics
+pub(crate) trait GlyphSource {
+    /// The pattern drawn for `ch`, if there is one
+    fn glyph(&self, ch: char) -> Option<&CharacterPattern>;
+    /// Number of rows above the baseline in a line of text
+    fn ascent(&self) -> usize;
+    /// Number of rows below the baseline in a line of text
+    fn descent(&self) -> usize;
+    /// Extra columns between `left` and a following `right`
+    fn kerning(&self, _left: char, _right: char) -> isize {
+        0
+    }
+}
+
+impl GlyphSource for PixelFont {
+    fn glyph(&self, ch: char) -> Option<&CharacterPattern> {
+        self.get_pattern(ch)
+    }
+
+    fn ascent(&self) -> usize {
+        self.ascent()
+    }
+
+    fn descent(&self) -> usize {
+        self.descent()
+    }
+
+    fn kerning(&self, left: char, right: char) -> isize {
+        self.kerning(left, right)
+    }
+}
+
+/// A glyph positioned inside the content area (padding excluded)
+pub(crate) struct Placement<'a> {
+    pub x: usize,
+    pub y: usize,
+    pub pattern: &'a CharacterPattern,
+}
+
+/// Where a single character of a line starts and how far it advances
+#[derive(Clone, Copy)]
+struct Advance<'a> {
+    ch: char,
+    x: usize,
+    width: usize,
+    pattern: Option<&'a CharacterPattern>,
+}
+
+/// A character positioned inside the content area (padding excluded)
+pub(crate) struct CharPosition {
+    pub ch: char,
+    pub line: usize,
+    pub x: usize,
+    pub advance: usize,
+}
+
+/// Glyph positions for a whole block of text
+pub(crate) struct Layout<'a> {
+    pub width: usize,
+    pub height: usize,
+    pub placements: Vec<Placement<'a>>,
+    pub chars: Vec<CharPosition>,
+}
+
+/// The error for a bitmap too wide to even describe
+fn too_wide(options: &RenderOptions) -> PixelArtError {
+    PixelArtError::BitmapTooWide {
+        width: usize::MAX,
+        limit: options.max_width.unwrap_or(usize::MAX),
+    }
+}
+
+/// The error for a bitmap too tall to even describe
+fn too_large(options: &RenderOptions) -> PixelArtError {
+    PixelArtError::BitmapTooLarge {
+        area: usize::MAX,
+        limit: options.max_area.unwrap_or(usize::MAX),
+    }
+}
+
+/// Lay out a single line, returning where each character goes and the line width
+///
+/// Fails if the line is too wide to describe.
+fn layout_line<'a, G: GlyphSource + ?Sized>(
+    chars: &[char],
+    font: &'a G,
+    options: &RenderOptions,
+) -> Result<(Vec<Advance<'a>>, usize), PixelArtError> {
+    let mut advances = Vec::with_capacity(chars.len());
+    let mut width = 0;
+    let mut cursor: isize = 0;
+    for (i, &ch) in chars.iter().enumerate() {
+        let pattern = if ch == ' ' { None } else { font.glyph(ch) };
+        let advance = match pattern {
+            Some(pattern) => pattern.width(),
+            None if ch == ' ' => options.word_spacing,
+            None => 0,
+        };
+        advances.push(Advance {
+            ch,
+            x: cursor as usize,
+            width: advance,
+            pattern,
+        });
+        cursor = isize::try_from(advance)
+            .ok()
+            .and_then(|advance| cursor.checked_add(advance))
+            .ok_or_else(|| too_wide(options))?;
+        width = width.max(cursor as usize);
+
+        // Add spacing between characters (except after the last character)
+        if let Some(&next) = chars.get(i + 1) {
+            cursor = cursor
+                .checked_add(options.letter_spacing)
+                .and_then(|cursor| cursor.checked_add(font.kerning(ch, next)))
+                .ok_or_else(|| too_wide(options))?
+                .max(0);
+        }
+    }
+    Ok((advances, width))
+}
+
+/// Width in pixels of a single line of characters, padding excluded
+pub(crate) fn line_width<G: GlyphSource + ?Sized>(
+    chars: &[char],
+    font: &G,
+    options: &RenderOptions,
+) -> Result<usize, PixelArtError> {
+    layout_line(chars, font, options).map(|(_, width)| width)
+}
+
+/// Lay out every line of `text`, aligning shorter lines within the widest one
+///
+/// Fails if the width or height of the text overflows.
+pub(crate) fn layout<'a, G, I, S>(
+    lines: I,
+    font: &'a G,
+    options: &RenderOptions,
+) -> Result<Layout<'a>, PixelArtError>
+where
+    G: GlyphSource + ?Sized,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let lines: Vec<_> = lines
+        .into_iter()
+        .map(|line| {
+            let chars: Vec<char> = line.as_ref().chars().collect();
+            layout_line(&chars, font, options)
+        })
+        .collect::<Result<_, _>>()?;
+
+    let width = lines.iter().map(|(_, width)| *width).max().unwrap_or(0);
+    let line_height = font.ascent() + font.descent();
+    let spacing_rows = lines
+        .len()
+        .saturating_sub(1)
+        .checked_mul(options.line_spacing);
+    let height = lines
+        .len()
+        .checked_mul(line_height)
+        .zip(spacing_rows)
+        .and_then(|(glyph_rows, spacing_rows)| glyph_rows.checked_add(spacing_rows))
+        .ok_or_else(|| too_large(options))?;
+
+    let char_count = lines.iter().map(|(advances, _)| advances.len()).sum();
+    let mut placements = Vec::with_capacity(char_count);
+    let mut chars = Vec::with_capacity(char_count);
+    for (line_idx, (advances, line_width)) in lines.into_iter().enumerate() {
+        let offset = match options.alignment {
+            Alignment::Left => 0,
+            Alignment::Center => (width - line_width) / 2,
+            Alignment::Right => width - line_width,
+        };
+        // Within the height checked above; the sum only overflows for a single line
+        let y = line_idx * line_height.saturating_add(options.line_spacing);
+        for advance in advances {
+            if let Some(pattern) = advance.pattern {
+                placements.push(Placement {
+                    x: advance.x + offset,
+                    y: y + font.ascent() - pattern.baseline(),
+                    pattern,
+                });
+            }
+            chars.push(CharPosition {
+                ch: advance.ch,
+                line: line_idx,
+                x: advance.x + offset,
+                advance: advance.width,
+            });
+        }
+    }
+
+    Ok(Layout {
+        width,
+        height,
+        placements,
+        chars,
+    })
+}
+
+impl Layout<'_> {
+    /// Width and height of the bitmap including the configured padding
+    ///
+    /// Fails if either overflows.
+    pub(crate) fn total_size(
+        &self,
+        options: &RenderOptions,
+    ) -> Result<(usize, usize), PixelArtError> {
+        let width = options
+            .padding_left
+            .checked_add(self.width)
+            .and_then(|width| width.checked_add(options.padding_right))
+            .ok_or_else(|| too_wide(options))?;
+        let height = options
+            .padding_top
+            .checked_add(self.height)
+            .and_then(|height| height.checked_add(options.padding_bottom))
+            .ok_or_else(|| too_large(options))?;
+        Ok((width, height))
+    }
+
+    /// Draw the laid out glyphs onto a bitmap of the size returned by
+    /// [`Layout::total_size`], surrounded by the configured padding
+    pub(crate) fn draw(
+        &self,
+        (total_width, total_height): (usize, usize),
+        options: &RenderOptions,
+    ) -> Bitmap {
+        let mut result = Bitmap::new(total_width, total_height);
+        if options.padding_fill {
+            result.fill(true);
+            result.fill_rect(
+                options.padding_left,
+                options.padding_top,
+                self.width,
+                self.height,
+                false,
+            );
+        }
+
+        // Glyphs are OR'ed so overlapping ones keep each other's pixels
+        for placement in &self.placements {
+            result.blit(
+                placement.pattern.bitmap(),
+                options.padding_left + placement.x,
+                options.padding_top + placement.y,
+                BlitOp::Or,
+            );
+        }
+
+        result
+    }
+}
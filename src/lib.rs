@@ -1,60 +1,428 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use diacritic::Composed;
+
+mod bdf;
+mod bitmap;
+mod color;
+mod diacritic;
+mod fit;
+mod font_text;
+mod format;
+mod image;
+mod layout;
+mod lossy;
+mod measure;
+mod netpbm;
+mod options;
+mod png;
+mod psf;
+mod renderer;
+mod stack;
+mod svg;
+mod terminal;
+mod translit;
+mod validate;
+mod wrap;
+
+pub use bitmap::{Bitmap, BlitOp};
+pub use color::Rgba;
+pub use diacritic::Mark;
+pub use fit::FittedRender;
+pub use format::TextFormat;
+pub use image::ImageOptions;
+pub use lossy::{LossyRender, Replacement, Substitution};
+pub use measure::{CharMetrics, Measurement};
+pub use netpbm::NetpbmEncoding;
+pub use options::{Alignment, RenderOptions};
+pub use renderer::Renderer;
+pub use stack::FontStack;
+pub use svg::{SvgOptions, SvgShape};
+pub use terminal::{ColorMode, TerminalOptions, TerminalStyle};
+pub use translit::{Transliterated, Transliteration};
+pub use validate::{UnsupportedChar, ValidationReport};
+pub use wrap::{BreakKind, LineBreak, WrappedText};
 
 /// Represents a variable-width character pattern
-#[derive(Debug, Clone)]
+///
+/// The pixels are stored bit-packed in a [`Bitmap`] as wide as the character.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CharacterPattern {
-    /// The pixel data for the character (5 rows, variable width)
-    pub pixels: Vec<Vec<u8>>,
-    /// The width of this character
-    pub width: usize,
+    bitmap: Bitmap,
+    baseline: usize,
 }
 
 impl CharacterPattern {
     /// Create a character pattern from variable width arrays
+    ///
+    /// The pattern sits entirely above the baseline; use
+    /// [`CharacterPattern::with_baseline`] to give it a descender.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows are not a valid glyph; see
+    /// [`CharacterPattern::try_new`] for a non-panicking version.
     pub fn new(rows: &[&[u8]]) -> Self {
-        assert_eq!(rows.len(), 5, "Character must have exactly 5 rows");
-        assert!(!rows.is_empty(), "Must provide at least one row");
-        
-        let width = rows[0].len();
-        assert!(width > 0, "Character width must be at least 1");
-        
-        let pixels = rows.iter().map(|row| {
-            assert_eq!(row.len(), width, "All rows must have the same width");
-            row.to_vec()
-        }).collect();
-        
-        Self { pixels, width }
+        match Self::try_new(rows) {
+            Ok(pattern) => pattern,
+            Err(error) => panic!("Invalid character pattern: {}", error),
+        }
+    }
+
+    /// Create a character pattern, checking that the rows form a valid glyph
+    ///
+    /// There must be at least one row, every row must have the same non-zero
+    /// width, and every pixel must be 0 or 1.
+    pub fn try_new(rows: &[&[u8]]) -> Result<Self, GlyphError> {
+        let width = rows.first().ok_or(GlyphError::NoRows)?.len();
+        if width == 0 {
+            return Err(GlyphError::ZeroWidth);
+        }
+
+        for (row_idx, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(GlyphError::RaggedRow {
+                    row: row_idx,
+                    expected: width,
+                    actual: row.len(),
+                });
+            }
+            if let Some((column, &value)) = row.iter().enumerate().find(|(_, &value)| value > 1) {
+                return Err(GlyphError::InvalidValue {
+                    row: row_idx,
+                    column,
+                    value,
+                });
+            }
+        }
+
+        Ok(Self::from_pixels(rows.iter().map(|row| row.to_vec()).collect()))
+    }
+
+    /// Create a character pattern from rows of any height, as produced by font importers
+    ///
+    /// The width is taken from the first row; any non-zero value lights a pixel.
+    pub(crate) fn from_pixels(pixels: Vec<Vec<u8>>) -> Self {
+        let width = pixels.first().map_or(0, Vec::len);
+        let mut bitmap = Bitmap::new(width, pixels.len());
+        for (y, row) in pixels.iter().enumerate() {
+            for (x, &pixel) in row.iter().take(width).enumerate() {
+                if pixel != 0 {
+                    bitmap.set(x, y, true);
+                }
+            }
+        }
+        Self::from_bitmap(bitmap, pixels.len())
+    }
+
+    /// Create a character pattern from a bitmap with `baseline` rows above the baseline
+    pub(crate) fn from_bitmap(bitmap: Bitmap, baseline: usize) -> Self {
+        Self { bitmap, baseline }
+    }
+
+    /// Set how many rows sit above the baseline, letting the rest drop below it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` is larger than the height of the pattern.
+    pub fn with_baseline(mut self, rows: usize) -> Self {
+        assert!(rows <= self.height(), "Baseline must lie within the character");
+        self.baseline = rows;
+        self
+    }
+
+    /// The width of this character
+    pub fn width(&self) -> usize {
+        self.bitmap.width()
+    }
+
+    /// Number of rows in this character
+    pub fn height(&self) -> usize {
+        self.bitmap.height()
+    }
+
+    /// Number of rows above the baseline; the remaining rows are the descender
+    pub fn baseline(&self) -> usize {
+        self.baseline
+    }
+
+    /// Number of rows below the baseline
+    pub fn descent(&self) -> usize {
+        self.height().saturating_sub(self.baseline)
+    }
+
+    /// Get the pixel at `(x, y)`, or `None` if it lies outside the character
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        self.bitmap.get(x, y)
+    }
+
+    /// The pixels of the character
+    pub fn bitmap(&self) -> &Bitmap {
+        &self.bitmap
     }
+
+    /// The pixels as rows of 0 and 1, the form taken by [`CharacterPattern::new`]
+    pub fn to_rows(&self) -> Vec<Vec<u8>> {
+        self.bitmap
+            .rows()
+            .map(|row| row.map(u8::from).collect())
+            .collect()
+    }
+
+    /// The pixel data as rows of 0 and 1
+    ///
+    /// This used to be the public `pixels` field. The rows are rebuilt from
+    /// the bit-packed storage on every call.
+    #[deprecated(note = "use `to_rows`, or `get` and `bitmap` to read pixels without copying")]
+    pub fn pixels(&self) -> Vec<Vec<u8>> {
+        self.to_rows()
+    }
+}
+
+/// Reasons a set of rows is not a valid character pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlyphError {
+    /// No rows were given
+    NoRows,
+    /// The rows have no pixels
+    ZeroWidth,
+    /// A row has a different width than the first row
+    RaggedRow {
+        /// 0-based index of the offending row
+        row: usize,
+        /// Width of the first row
+        expected: usize,
+        /// Width of the offending row
+        actual: usize,
+    },
+    /// A pixel is something other than 0 or 1
+    InvalidValue {
+        /// 0-based index of the offending row
+        row: usize,
+        /// 0-based index of the offending column
+        column: usize,
+        /// The value found
+        value: u8,
+    },
 }
 
+impl fmt::Display for GlyphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlyphError::NoRows => write!(f, "character must have at least one row"),
+            GlyphError::ZeroWidth => write!(f, "character width must be at least 1"),
+            GlyphError::RaggedRow { row, expected, actual } => {
+                write!(f, "row {} is {} pixels wide, expected {}", row, actual, expected)
+            }
+            GlyphError::InvalidValue { row, column, value } => {
+                write!(f, "pixel at row {}, column {} is {}, expected 0 or 1", row, column, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GlyphError {}
+
 /// Errors that can occur when working with pixel art
 #[derive(Debug, Clone, PartialEq)]
 pub enum PixelArtError {
-    /// Input text is too long to process
-    TextTooLong(usize),
+    /// Input text has more characters than the configured limit
+    TextTooLong {
+        /// Number of characters in the text
+        length: usize,
+        /// Configured maximum, see [`RenderOptions::max_chars`]
+        limit: usize,
+    },
+    /// The rendered bitmap would be wider than the configured limit
+    BitmapTooWide {
+        /// Width the bitmap would have, in pixels
+        width: usize,
+        /// Configured maximum, see [`RenderOptions::max_width`]
+        limit: usize,
+    },
+    /// The rendered bitmap would have more pixels than the configured limit
+    BitmapTooLarge {
+        /// Number of pixels the bitmap would have
+        area: usize,
+        /// Configured maximum, see [`RenderOptions::max_area`]
+        limit: usize,
+    },
     /// Unsupported character in input
     UnsupportedCharacter(char),
+    /// The wrap width leaves no room for even a single character
+    WrapWidthTooSmall(usize),
+    /// Text is too wide for the target width even with the tightest spacing and every font tried
+    DoesNotFit {
+        /// Narrowest width that could be reached, in pixels
+        width: usize,
+        /// Width the text had to fit in
+        target: usize,
+    },
+    /// A font or glyph definition is invalid
+    InvalidFont(FontError),
 }
 
 impl fmt::Display for PixelArtError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PixelArtError::TextTooLong(len) => {
-                write!(f, "Text too long: {} characters (max: 1000)", len)
+            PixelArtError::TextTooLong { length, limit } => {
+                write!(f, "Text too long: {} characters (max: {})", length, limit)
+            }
+            PixelArtError::BitmapTooWide { width, limit } => {
+                write!(f, "Rendered text too wide: {} pixels (max: {})", width, limit)
+            }
+            PixelArtError::BitmapTooLarge { area, limit } => {
+                write!(f, "Rendered text too large: {} pixels (max: {})", area, limit)
             }
             PixelArtError::UnsupportedCharacter(ch) => {
                 write!(f, "Unsupported character: '{}'", ch)
             }
+            PixelArtError::WrapWidthTooSmall(width) => {
+                write!(f, "Wrap width {} is too small to fit a single character", width)
+            }
+            PixelArtError::DoesNotFit { width, target } => write!(
+                f,
+                "Text does not fit in {} pixels: {} pixels at its narrowest",
+                target, width
+            ),
+            PixelArtError::InvalidFont(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PixelArtError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PixelArtError::InvalidFont(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<FontError> for PixelArtError {
+    fn from(error: FontError) -> Self {
+        PixelArtError::InvalidFont(error)
+    }
+}
+
+impl From<GlyphError> for PixelArtError {
+    fn from(error: GlyphError) -> Self {
+        PixelArtError::InvalidFont(error.into())
+    }
+}
+
+/// Errors that can occur when loading a font
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontError {
+    /// The font could not be read
+    Io(String),
+    /// A binary font file is not in the expected format
+    InvalidFormat(String),
+    /// A glyph built in code is invalid
+    InvalidGlyph(GlyphError),
+    /// The font definition is malformed
+    Parse {
+        /// 1-based line number of the problem
+        line: usize,
+        /// What was wrong with the line
+        kind: ParseErrorKind,
+    },
+}
+
+/// The specific problem found while parsing a font definition
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// Pixel rows appeared before any glyph header
+    MissingHeader,
+    /// A glyph header did not name a single character or `U+XXXX` code point
+    InvalidCharacter(String),
+    /// The same character was defined twice
+    DuplicateGlyph(char),
+    /// The same pair of characters was kerned twice
+    DuplicateKerning(char, char),
+    /// A pixel row contained something other than `#`, `.`, `1` or `0`
+    InvalidPixel(char),
+    /// A pixel row was a different width than the first row of its glyph
+    RaggedRow { expected: usize, actual: usize },
+    /// A glyph had the wrong number of pixel rows
+    WrongRowCount { expected: usize, actual: usize },
+    /// A required property or keyword was never given
+    MissingProperty(String),
+    /// A value could not be understood
+    InvalidValue(String),
+    /// The input ended in the middle of a glyph
+    UnexpectedEnd,
+    /// A font-wide setting appeared after the first glyph
+    MisplacedDirective(String),
+    /// The rows of a glyph do not form a valid character pattern
+    InvalidGlyph(GlyphError),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(message) => write!(f, "Failed to read font: {}", message),
+            FontError::InvalidFormat(message) => write!(f, "Invalid font file: {}", message),
+            FontError::InvalidGlyph(error) => write!(f, "Invalid glyph: {}", error),
+            FontError::Parse { line, kind } => write!(f, "Font error on line {}: {}", line, kind),
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::MissingHeader => write!(f, "pixel row outside of a glyph"),
+            ParseErrorKind::InvalidCharacter(spec) => write!(f, "invalid glyph character '{}'", spec),
+            ParseErrorKind::DuplicateGlyph(ch) => write!(f, "glyph '{}' is defined twice", ch),
+            ParseErrorKind::DuplicateKerning(left, right) => {
+                write!(f, "pair '{}{}' is kerned twice", left, right)
+            }
+            ParseErrorKind::InvalidPixel(ch) => write!(f, "invalid pixel '{}'", ch),
+            ParseErrorKind::RaggedRow { expected, actual } => {
+                write!(f, "row is {} pixels wide, expected {}", actual, expected)
+            }
+            ParseErrorKind::WrongRowCount { expected, actual } => {
+                write!(f, "glyph has {} rows, expected {}", actual, expected)
+            }
+            ParseErrorKind::MissingProperty(name) => write!(f, "missing {}", name),
+            ParseErrorKind::InvalidValue(value) => write!(f, "invalid value '{}'", value),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of font"),
+            ParseErrorKind::MisplacedDirective(name) => {
+                write!(f, "'{}' must come before the first glyph", name)
+            }
+            ParseErrorKind::InvalidGlyph(error) => write!(f, "{}", error),
         }
     }
 }
 
-impl std::error::Error for PixelArtError {}
+impl std::error::Error for FontError {}
+
+impl From<io::Error> for FontError {
+    fn from(error: io::Error) -> Self {
+        FontError::Io(error.to_string())
+    }
+}
+
+impl From<GlyphError> for FontError {
+    fn from(error: GlyphError) -> Self {
+        FontError::InvalidGlyph(error)
+    }
+}
 
 /// Font data structure containing variable-width character patterns
+#[derive(Debug, Clone, PartialEq)]
 pub struct PixelFont {
     characters: HashMap<char, CharacterPattern>,
+    kerning: HashMap<(char, char), isize>,
+    ascent: usize,
+    descent: usize,
 }
 
 impl Default for PixelFont {
@@ -65,6 +433,9 @@ impl Default for PixelFont {
 
 impl PixelFont {
     /// Create a new font with basic character set
+    ///
+    /// This builds every glyph from scratch; use [`PixelFont::builtin`] to
+    /// share a single copy instead.
     pub fn new() -> Self {
         let mut characters = HashMap::with_capacity(100);
         
@@ -813,7 +1184,152 @@ impl PixelFont {
             &[0, 0],
         ]));
 
-        PixelFont { characters }
+        PixelFont {
+            characters,
+            kerning: HashMap::new(),
+            ascent: 5,
+            descent: 0,
+        }
+    }
+
+    /// The built-in font, built once on first use and shared afterwards
+    pub fn builtin() -> &'static PixelFont {
+        static BUILTIN: OnceLock<PixelFont> = OnceLock::new();
+        BUILTIN.get_or_init(PixelFont::new)
+    }
+
+    /// Create a font without any characters
+    ///
+    /// The font grows to fit the characters inserted into it.
+    pub fn empty() -> Self {
+        Self::with_metrics(0, 0)
+    }
+
+    /// Create a font without any characters but with a fixed line height
+    ///
+    /// `ascent` rows sit above the baseline and `descent` rows below it.
+    /// Characters taller than that still grow the font when inserted.
+    pub fn with_metrics(ascent: usize, descent: usize) -> Self {
+        PixelFont {
+            characters: HashMap::new(),
+            kerning: HashMap::new(),
+            ascent,
+            descent,
+        }
+    }
+
+    /// Load a font from its plain-text definition
+    ///
+    /// See the format description on [`PixelFont::to_text`].
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, FontError> {
+        font_text::parse(BufReader::new(reader))
+    }
+
+    /// Load a font from a plain-text definition file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Import a font in X11 BDF format
+    ///
+    /// Glyphs keep their bounding box offsets and use their `DWIDTH` as their
+    /// width. Since BDF advances already include the gap between characters,
+    /// such fonts are usually rendered with a letter spacing of 0.
+    pub fn from_bdf_reader<R: Read>(reader: R) -> Result<Self, FontError> {
+        bdf::parse(BufReader::new(reader))
+    }
+
+    /// Import a font from an X11 BDF file
+    pub fn from_bdf_path<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        Self::from_bdf_reader(File::open(path)?)
+    }
+
+    /// Import a PSF1 or PSF2 Linux console font
+    ///
+    /// Characters are taken from the font's unicode table when it has one and
+    /// from the glyph index otherwise. Console glyphs share one fixed width
+    /// that already includes the gap between characters, so such fonts are
+    /// usually rendered with a letter spacing of 0.
+    pub fn from_psf_reader<R: Read>(reader: R) -> Result<Self, FontError> {
+        psf::parse(reader)
+    }
+
+    /// Import a PSF1 or PSF2 Linux console font file
+    pub fn from_psf_path<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        Self::from_psf_reader(File::open(path)?)
+    }
+
+    /// Write the font as a PSF2 console font with a unicode table
+    ///
+    /// Glyphs are left aligned in a cell as wide as the widest glyph and a
+    /// blank glyph is added for the space character.
+    pub fn write_psf2<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        psf::write_psf2(self, writer)
+    }
+
+    /// Write the font as a PSF1 console font with a unicode table
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if a glyph is wider than 8
+    /// pixels or the font has more than 512 glyphs.
+    pub fn write_psf1<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        psf::write_psf1(self, writer)
+    }
+
+    /// Format the font as a plain-text definition
+    ///
+    /// Each glyph is a `char X` header (or `char U+XXXX` for invisible
+    /// characters) followed by its rows, using `#` (or `1`) for lit pixels and
+    /// `.` (or `0`) for blank ones. Lines starting with `//` are comments.
+    /// Fonts that are not 5 rows tall, or that have descenders, start with
+    /// `height N` and `baseline N` lines giving the rows per glyph and the
+    /// rows above the baseline.
+    pub fn to_text(&self) -> String {
+        font_text::to_string(self)
+    }
+
+    /// Write the font as a plain-text definition
+    pub fn write_text<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        font_text::write(self, writer)
+    }
+
+    /// Add or replace the pattern for a character
+    ///
+    /// The font's ascent and descent grow if the pattern does not fit them.
+    pub fn insert(&mut self, ch: char, pattern: CharacterPattern) -> Option<CharacterPattern> {
+        self.ascent = self.ascent.max(pattern.baseline);
+        self.descent = self.descent.max(pattern.descent());
+        self.characters.insert(ch, pattern)
+    }
+
+    /// Height of a line of text in this font, in rows
+    pub fn height(&self) -> usize {
+        self.ascent + self.descent
+    }
+
+    /// Number of rows above the baseline
+    pub fn ascent(&self) -> usize {
+        self.ascent
+    }
+
+    /// Number of rows below the baseline
+    pub fn descent(&self) -> usize {
+        self.descent
+    }
+
+    /// Row of a line at which the top of a pattern is drawn so it sits on the baseline
+    pub(crate) fn glyph_top(&self, pattern: &CharacterPattern) -> usize {
+        self.ascent - pattern.baseline
+    }
+
+    /// Row `y` of a line as covered by `pattern`, or `None` if the pattern does not reach it
+    pub(crate) fn cell_row<'a>(
+        &self,
+        pattern: &'a CharacterPattern,
+        y: usize,
+    ) -> Option<impl Iterator<Item = bool> + 'a> {
+        y.checked_sub(self.glyph_top(pattern))
+            .filter(|&row| row < pattern.height())
+            .map(|row| pattern.bitmap.row(row))
     }
 
     /// Get the pattern for a specific character
@@ -821,90 +1337,342 @@ impl PixelFont {
         self.characters.get(&ch)
     }
 
+    /// Returns true if the font has a pattern for `ch`
+    pub fn supports_char(&self, ch: char) -> bool {
+        self.characters.contains_key(&ch)
+    }
+
     /// Get all supported characters
     pub fn supported_characters(&self) -> Vec<char> {
         let mut chars: Vec<char> = self.characters.keys().cloned().collect();
         chars.sort();
         chars
     }
-}
 
-/// Validates that all characters in the input text are supported
-fn validate_text(text: &str, font: &PixelFont) -> Result<(), PixelArtError> {
-    for ch in text.chars() {
-        if ch == ' ' {
-            continue; // Space is handled specially
-        }
-        if !font.characters.contains_key(&ch) {
-            return Err(PixelArtError::UnsupportedCharacter(ch));
+    /// Set the extra columns between `left` and a following `right`
+    ///
+    /// The adjustment is added to the letter spacing; negative values pull
+    /// the pair closer and may make it overlap, in which case the pixels are
+    /// combined. An adjustment of 0 removes the pair.
+    pub fn set_kerning(&mut self, left: char, right: char, columns: isize) {
+        if columns == 0 {
+            self.kerning.remove(&(left, right));
+        } else {
+            self.kerning.insert((left, right), columns);
         }
     }
-    Ok(())
-}
 
-/// Convert text to pixel art representation
-pub fn text_to_pixel_art(text: &str) -> Result<String, PixelArtError> {
-    if text.is_empty() {
-        return Ok(String::new());
+    /// Extra columns between `left` and a following `right`, 0 if the pair is not kerned
+    pub fn kerning(&self, left: char, right: char) -> isize {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0)
     }
 
-    let font = PixelFont::new();
-    validate_text(text, &font)?;
+    /// Every kerned pair with its adjustment, sorted by pair
+    pub fn kerning_pairs(&self) -> Vec<((char, char), isize)> {
+        let mut pairs: Vec<_> = self
+            .kerning
+            .iter()
+            .map(|(&pair, &columns)| (pair, columns))
+            .collect();
+        pairs.sort();
+        pairs
+    }
+}
 
-    let chars: Vec<char> = text.chars().collect();
-    
-    // Calculate total width needed
-    let mut content_width = 0;
-    for (i, &ch) in chars.iter().enumerate() {
-        if ch == ' ' {
-            content_width += 2; // Space width
-        } else if let Some(pattern) = font.get_pattern(ch) {
-            content_width += pattern.width;
-        }
-        
-        // Add spacing between characters (except after the last character)
-        if i < chars.len() - 1 {
-            content_width += 1;
+/// Checks the text against the configured character limit
+fn check_length(text: &str, options: &RenderOptions) -> Result<(), PixelArtError> {
+    if let Some(limit) = options.max_chars {
+        let length = text.chars().count();
+        if length > limit {
+            return Err(PixelArtError::TextTooLong { length, limit });
         }
     }
+    Ok(())
+}
 
-    // Add padding: 1 pixel on each side horizontally, 1 pixel on top and bottom vertically
-    let total_width = content_width + 2;
-    let total_height = 7; // 5 rows for characters + 1 row padding top + 1 row padding bottom
-    
-    // Pre-allocate the result grid with padding
-    let mut result = vec![vec![0u8; total_width]; total_height];
-    let mut current_x = 1; // Start at x=1 to account for left padding
-
-    for (i, &ch) in chars.iter().enumerate() {
-        if ch == ' ' {
-            current_x += 2;
-        } else if let Some(pattern) = font.get_pattern(ch) {
-            // Copy character pattern to result (offset by 1 row for top padding)
-            for (row_idx, row) in pattern.pixels.iter().enumerate() {
-                for (col_idx, &pixel) in row.iter().enumerate() {
-                    result[row_idx + 1][current_x + col_idx] = pixel;
-                }
-            }
-            current_x += pattern.width;
+/// Checks the size of the bitmap a layout would produce against the configured
+/// limits, returning the size
+fn check_size(layout: &layout::Layout, options: &RenderOptions) -> Result<(usize, usize), PixelArtError> {
+    let (width, height) = layout.total_size(options)?;
+    if let Some(limit) = options.max_width {
+        if width > limit {
+            return Err(PixelArtError::BitmapTooWide { width, limit });
         }
-        
-        // Add spacing between characters (except after the last character)
-        if i < chars.len() - 1 {
-            current_x += 1; // Single column spacing
+    }
+    if let Some(limit) = options.max_area {
+        let area = width.saturating_mul(height);
+        if area > limit {
+            return Err(PixelArtError::BitmapTooLarge { area, limit });
         }
     }
+    Ok((width, height))
+}
+
+/// Check text against the limits and the available glyphs, then wrap it
+fn wrap_checked<G: layout::GlyphSource>(
+    text: &str,
+    glyphs: &G,
+    options: &RenderOptions,
+) -> Result<WrappedText, PixelArtError> {
+    check_length(text, options)?;
+    let prepared = options
+        .transliterate
+        .then(|| translit::prepare(text, glyphs, true, false));
+    let prepared_text = prepared.as_ref().map_or(text, |prepared| prepared.text.as_str());
+    if let Some(unsupported) = validate::unsupported_chars(prepared_text, glyphs).next() {
+        return Err(PixelArtError::UnsupportedCharacter(unsupported.ch));
+    }
 
-    // Convert to string representation
-    let mut output = String::with_capacity(total_width * (total_height + 1)); // total_height rows + newlines
-    for row in result {
-        for pixel in row {
-            output.push(if pixel == 1 { '1' } else { '0' });
+    let mut wrapped = wrap::wrap(prepared_text, glyphs, options)?;
+    if let Some(prepared) = prepared {
+        // Breaks should point into the text the caller passed in
+        let origins = prepared.origins(text);
+        for line_break in &mut wrapped.breaks {
+            line_break.char_index = origins[line_break.char_index];
         }
-        output.push('\n');
+    }
+    Ok(wrapped)
+}
+
+/// Render text with any source of glyphs
+fn render_glyphs<G: layout::GlyphSource>(
+    font: &G,
+    text: &str,
+    options: &RenderOptions,
+) -> Result<Bitmap, PixelArtError> {
+    if text.is_empty() {
+        return Ok(Bitmap::default());
+    }
+
+    let glyphs = Composed::new(font, text, options.diacritics);
+    let wrapped = wrap_checked(text, &glyphs, options)?;
+    draw_lines(&wrapped.lines, &glyphs, options)
+}
+
+/// Measure text with any source of glyphs, without checking the size limits
+fn measure_glyphs<G: layout::GlyphSource>(
+    font: &G,
+    text: &str,
+    options: &RenderOptions,
+) -> Result<Measurement, PixelArtError> {
+    if text.is_empty() {
+        return Ok(Measurement::default());
+    }
+
+    let glyphs = Composed::new(font, text, options.diacritics);
+    let wrapped = wrap_checked(text, &glyphs, options)?;
+    let layout = layout::layout(&wrapped.lines, &glyphs, options)?;
+    measure::measure(&layout, options)
+}
+
+/// Wrap text with any source of glyphs
+fn wrap_glyphs<G: layout::GlyphSource>(
+    font: &G,
+    text: &str,
+    options: &RenderOptions,
+) -> Result<WrappedText, PixelArtError> {
+    let glyphs = Composed::new(font, text, options.diacritics);
+    wrap_checked(text, &glyphs, options)
+}
+
+/// Lay out already wrapped lines and draw them, checking the size limits first
+fn draw_lines<G: layout::GlyphSource>(
+    lines: &[String],
+    glyphs: &G,
+    options: &RenderOptions,
+) -> Result<Bitmap, PixelArtError> {
+    let layout = layout::layout(lines, glyphs, options)?;
+    let size = check_size(&layout, options)?;
+    Ok(layout.draw(size, options))
+}
+
+impl PixelFont {
+    /// Render text into a bitmap with this font
+    ///
+    /// Text may span several lines separated by `\n` (or `\r\n`).
+    ///
+    /// The size limits in `options` are checked before the bitmap is allocated.
+    pub fn render(&self, text: &str, options: &RenderOptions) -> Result<Bitmap, PixelArtError> {
+        render_glyphs(self, text, options)
+    }
+
+    /// Render text, drawing a replacement for every unsupported character
+    ///
+    /// What is drawn instead is chosen with [`RenderOptions::replacement`].
+    /// Other errors, such as exceeding the size limits, are still reported.
+    pub fn render_lossy(&self, text: &str, options: &RenderOptions) -> Result<LossyRender, PixelArtError> {
+        lossy::render(self, text, options)
+    }
+
+    /// Work out how text would be split into lines without rendering it
+    ///
+    /// Lines are only wrapped when [`RenderOptions::wrap_width`] is set.
+    ///
+    /// With [`RenderOptions::transliterate`] set, the lines hold the
+    /// transliterated text, while each [`LineBreak::char_index`] still refers
+    /// to `text` as given.
+    pub fn wrap_text(&self, text: &str, options: &RenderOptions) -> Result<WrappedText, PixelArtError> {
+        wrap_glyphs(self, text, options)
+    }
+
+    /// Work out the size of the bitmap text would render to, and where each
+    /// character would go, without drawing anything
+    ///
+    /// Uses the same layout as [`PixelFont::render`], so the sizes always
+    /// agree. The size limits in `options` are not applied, which makes this
+    /// suitable for checking whether text fits before rendering it.
+    pub fn measure(&self, text: &str, options: &RenderOptions) -> Result<Measurement, PixelArtError> {
+        measure_glyphs(self, text, options)
+    }
+
+    /// Render text no wider than `width` pixels, tightening the spacing as needed
+    ///
+    /// See [`render_to_fit`] for the order in which spacings are tried.
+    pub fn render_to_fit(
+        &self,
+        text: &str,
+        width: usize,
+        options: &RenderOptions,
+    ) -> Result<FittedRender, PixelArtError> {
+        fit::fit(std::slice::from_ref(self), text, width, options)
+    }
+
+    /// Build the accented glyph for `ch` from this font's glyph for its base letter
+    ///
+    /// Returns `None` if `ch` is not a letter with a supported [`Mark`] or the
+    /// base letter is missing. The result may be taller than the font; see
+    /// [`RenderOptions::diacritics`].
+    pub fn compose(&self, ch: char) -> Option<CharacterPattern> {
+        diacritic::compose_char(self, ch)
+    }
+}
+
+impl PixelFont {
+    /// Find every character in `text` that this font cannot render
+    ///
+    /// Spaces and line breaks are always supported.
+    pub fn validate(&self, text: &str) -> ValidationReport {
+        validate::validate(text, self)
     }
 
-    Ok(output)
+    /// Replace unsupported characters with fallback spellings this font can draw
+    ///
+    /// Accents are stripped (`é` → `e`), ligatures and special letters are
+    /// spelled out (`ß` → `ss`) and typographic punctuation becomes plain ASCII
+    /// (curly quotes → straight quotes, dashes → `-`). A fallback is only used
+    /// if the font has every character in it; anything else is left for
+    /// [`PixelFont::validate`] to report.
+    pub fn transliterate(&self, text: &str) -> Transliterated {
+        translit::transliterate(text, self)
+    }
+}
+
+impl FromStr for PixelFont {
+    type Err = FontError;
+
+    /// Parse a font from its plain-text definition
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        font_text::parse(s.as_bytes())
+    }
+}
+
+/// Render text into a bitmap using the built-in font
+pub fn render(text: &str) -> Result<Bitmap, PixelArtError> {
+    render_with_options(text, &RenderOptions::default())
+}
+
+/// Render text into a bitmap using the built-in font and custom layout options
+///
+/// Text may span several lines separated by `\n` (or `\r\n`).
+pub fn render_with_options(text: &str, options: &RenderOptions) -> Result<Bitmap, PixelArtError> {
+    PixelFont::builtin().render(text, options)
+}
+
+/// Render text using the built-in font, drawing a replacement for every
+/// unsupported character instead of failing
+pub fn render_lossy(text: &str, options: &RenderOptions) -> Result<LossyRender, PixelArtError> {
+    PixelFont::builtin().render_lossy(text, options)
+}
+
+/// Work out the size and character positions of text rendered with the built-in font
+pub fn measure(text: &str, options: &RenderOptions) -> Result<Measurement, PixelArtError> {
+    PixelFont::builtin().measure(text, options)
+}
+
+/// Render text no wider than `width` pixels, padding included, choosing the
+/// spacing and font automatically
+///
+/// Each font is tried in order, first with the letter spacing from `options`
+/// reduced one column at a time down to 0, then with the word spacing reduced
+/// down to 1. The first combination that fits is rendered and reported. With
+/// an empty `fonts` list the built-in font is used.
+pub fn render_to_fit(
+    text: &str,
+    width: usize,
+    fonts: &[PixelFont],
+    options: &RenderOptions,
+) -> Result<FittedRender, PixelArtError> {
+    if fonts.is_empty() {
+        PixelFont::builtin().render_to_fit(text, width, options)
+    } else {
+        fit::fit(fonts, text, width, options)
+    }
+}
+
+/// Work out how text would be split into lines without rendering it
+///
+/// Lines are only wrapped when [`RenderOptions::wrap_width`] is set.
+pub fn wrap_text(text: &str, options: &RenderOptions) -> Result<WrappedText, PixelArtError> {
+    PixelFont::builtin().wrap_text(text, options)
+}
+
+/// Replace characters the built-in font cannot draw with fallback spellings
+///
+/// Run this ahead of [`validate_text`] to accept input like "José" or "Straße".
+pub fn transliterate(text: &str) -> Transliterated {
+    PixelFont::builtin().transliterate(text)
+}
+
+/// Check that every character in `text` is supported by the built-in font
+///
+/// On failure the report lists every unsupported character with its position
+/// and displays as a caret-style diagnostic.
+pub fn validate_text(text: &str) -> Result<(), ValidationReport> {
+    let report = PixelFont::builtin().validate(text);
+    if report.is_valid() {
+        Ok(())
+    } else {
+        Err(report)
+    }
+}
+
+/// Convert text to pixel art representation
+///
+/// This is the '1'/'0' text form of [`render`].
+pub fn text_to_pixel_art(text: &str) -> Result<String, PixelArtError> {
+    render(text).map(|bitmap| bitmap.to_string())
+}
+
+/// Convert text to pixel art written with the strings of `format`
+///
+/// ```
+/// use text_to_input::{text_to_pixel_art_with_format, TextFormat};
+///
+/// let art = text_to_pixel_art_with_format("Hi", &TextFormat::hash()).unwrap();
+/// assert!(art.lines().all(|line| line.chars().all(|ch| ch == '#' || ch == ' ')));
+/// ```
+pub fn text_to_pixel_art_with_format(
+    text: &str,
+    format: &TextFormat,
+) -> Result<String, PixelArtError> {
+    render(text).map(|bitmap| bitmap.format(format))
+}
+
+/// Convert text to pixel art, drawing a tofu box for every unsupported character
+///
+/// Use [`render_lossy`] to pick another replacement or to find out what was replaced.
+pub fn text_to_pixel_art_lossy(text: &str) -> Result<String, PixelArtError> {
+    render_lossy(text, &RenderOptions::default()).map(|result| result.bitmap.to_string())
 }
 
 #[cfg(test)]
@@ -921,9 +1689,60 @@ mod tests {
             &[1, 0, 1],
         ]);
         
-        assert_eq!(pattern.width, 3);
-        assert_eq!(pattern.pixels.len(), 5);
-        assert_eq!(pattern.pixels[0], vec![1, 0, 1]);
+        assert_eq!(pattern.width(), 3);
+        #[allow(deprecated)]
+        {
+            assert_eq!(pattern.pixels().len(), 5);
+            assert_eq!(pattern.pixels()[0], vec![1, 0, 1]);
+        }
+    }
+
+    #[test]
+    fn test_character_pattern_bitmap() {
+        let pattern = CharacterPattern::new(&[&[1, 0, 1], &[0, 1, 0]]);
+        assert_eq!(pattern.height(), 2);
+        assert_eq!(pattern.to_rows()[0], vec![1, 0, 1]);
+        assert_eq!(pattern.get(1, 1), Some(true));
+        assert_eq!(pattern.get(3, 0), None);
+    }
+
+    #[test]
+    fn test_character_pattern_try_new() {
+        assert_eq!(CharacterPattern::try_new(&[]), Err(GlyphError::NoRows));
+        assert_eq!(CharacterPattern::try_new(&[&[], &[]]), Err(GlyphError::ZeroWidth));
+        assert_eq!(
+            CharacterPattern::try_new(&[&[1, 0], &[1]]),
+            Err(GlyphError::RaggedRow { row: 1, expected: 2, actual: 1 })
+        );
+        assert_eq!(
+            CharacterPattern::try_new(&[&[1, 0], &[1, 2]]),
+            Err(GlyphError::InvalidValue { row: 1, column: 1, value: 2 })
+        );
+        assert!(CharacterPattern::try_new(&[&[1, 0], &[0, 1]]).is_ok());
+
+        let error = PixelArtError::from(GlyphError::ZeroWidth);
+        assert_eq!(error, PixelArtError::InvalidFont(FontError::InvalidGlyph(GlyphError::ZeroWidth)));
+        assert_eq!(error.to_string(), "Invalid glyph: character width must be at least 1");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 is 1 pixels wide, expected 2")]
+    fn test_character_pattern_new_panics_on_ragged_rows() {
+        CharacterPattern::new(&[&[1, 0], &[1]]);
+    }
+
+    #[test]
+    fn test_character_pattern_baseline() {
+        let pattern = CharacterPattern::new(&[&[1], &[1], &[1]]).with_baseline(2);
+        assert_eq!(pattern.height(), 3);
+        assert_eq!(pattern.descent(), 1);
+
+        let mut font = PixelFont::with_metrics(4, 0);
+        font.insert('j', pattern);
+        assert_eq!(font.ascent(), 4);
+        assert_eq!(font.descent(), 1);
+        assert_eq!(font.height(), 5);
+        assert_eq!(font.glyph_top(font.get_pattern('j').unwrap()), 2);
     }
 
     #[test]
@@ -1019,4 +1838,262 @@ mod tests {
             assert_eq!(line.len(), first_line_len);
         }
     }
+
+    #[test]
+    fn test_render_matches_text_output() {
+        let bitmap = render("Hi").unwrap();
+        assert_eq!(bitmap.height(), 7);
+        assert_eq!(bitmap.width(), 4 + 1 + 1 + 2);
+        assert_eq!(bitmap.to_string(), text_to_pixel_art("Hi").unwrap());
+
+        // Padding stays blank, the first column of 'H' is lit
+        assert_eq!(bitmap.get(0, 1), Some(false));
+        assert_eq!(bitmap.get(1, 0), Some(false));
+        assert_eq!(bitmap.get(1, 1), Some(true));
+    }
+
+    #[test]
+    fn test_render_options_padding() {
+        let options = RenderOptions::new()
+            .padding_top(0)
+            .padding_bottom(2)
+            .padding_left(3)
+            .padding_right(0);
+        let bitmap = render_with_options("i", &options).unwrap();
+        assert_eq!(bitmap.width(), 4);
+        assert_eq!(bitmap.height(), 7);
+        assert_eq!(bitmap.get(3, 0), Some(true));
+
+        let filled = render_with_options("i", &RenderOptions::new().padding_fill(true)).unwrap();
+        assert_eq!(filled.get(0, 0), Some(true));
+        assert_eq!(filled.get(2, 6), Some(true));
+        assert_eq!(filled.get(1, 2), Some(false));
+    }
+
+    #[test]
+    fn test_render_options_spacing() {
+        let tight = render_with_options("ii", &RenderOptions::new().letter_spacing(0)).unwrap();
+        assert_eq!(tight.width(), 2 + 2);
+
+        // Overlapping glyphs are combined rather than overwriting each other
+        let overlap = render_with_options("HH", &RenderOptions::new().letter_spacing(-1)).unwrap();
+        assert_eq!(overlap.width(), 7 + 2);
+        assert_eq!(overlap.get(4, 1), Some(true));
+
+        let wide = render_with_options("i i", &RenderOptions::new().word_spacing(5)).unwrap();
+        assert_eq!(wide.width(), 1 + 1 + 5 + 1 + 1 + 2);
+
+        // Spacing too wide to lay out is rejected rather than wrapping around
+        for options in [
+            RenderOptions::new().word_spacing(usize::MAX),
+            RenderOptions::new().letter_spacing(isize::MAX),
+        ] {
+            assert!(matches!(
+                render_with_options("H H", &options.max_width(None)),
+                Err(PixelArtError::BitmapTooWide { width: usize::MAX, .. })
+            ));
+        }
+        // Wrapping breaks at the space instead
+        let options = RenderOptions::new().word_spacing(usize::MAX).wrap_width(20);
+        assert_eq!(wrap_text("H H", &options).unwrap().lines, vec!["H", "H"]);
+    }
+
+    #[test]
+    fn test_multiline_render() {
+        let bitmap = render("Hi\ni").unwrap();
+        assert_eq!(bitmap.height(), 1 + 5 + 1 + 5 + 1);
+        assert_eq!(bitmap.width(), 6 + 2);
+        assert_eq!(render("Hi\r\ni").unwrap(), bitmap);
+
+        let spaced = render_with_options("i\ni", &RenderOptions::new().line_spacing(3)).unwrap();
+        assert_eq!(spaced.height(), 1 + 5 + 3 + 5 + 1);
+    }
+
+    #[test]
+    fn test_multiline_alignment() {
+        // 'H' is 4 columns wide, 'i' is a single lit column on its row 2
+        let right = render_with_options("H\ni", &RenderOptions::new().alignment(Alignment::Right)).unwrap();
+        assert_eq!(right.get(4, 9), Some(true));
+
+        let center = render_with_options("H\ni", &RenderOptions::new().alignment(Alignment::Center)).unwrap();
+        assert_eq!(center.get(2, 9), Some(true));
+
+        let left = render("H\ni").unwrap();
+        assert_eq!(left.get(1, 9), Some(true));
+    }
+
+    #[test]
+    fn test_render_wrapped() {
+        let options = RenderOptions::new().wrap_width(9);
+        let bitmap = render_with_options("ill ill", &options).unwrap();
+        assert_eq!(bitmap.width(), 9);
+        assert_eq!(bitmap.height(), 13);
+        assert_eq!(bitmap, render("ill\nill").unwrap());
+        assert_eq!(wrap_text("ill ill", &options).unwrap().line_count(), 2);
+    }
+
+    #[test]
+    fn test_render_with_loaded_font() {
+        let font: PixelFont = "char x\n#.#\n.#.\n#.#\n...\n...\n".parse().unwrap();
+        let bitmap = font.render("xx", &RenderOptions::new().padding(0)).unwrap();
+        assert_eq!(bitmap.to_string(), "1010101\n0100010\n1010101\n0000000\n0000000\n");
+
+        assert!(matches!(
+            font.render("y", &RenderOptions::new()),
+            Err(PixelArtError::UnsupportedCharacter('y'))
+        ));
+    }
+
+    #[test]
+    fn test_render_descenders() {
+        // A 3-row micro font where 'q' drops one row below the baseline
+        let mut font = PixelFont::empty();
+        font.insert('o', CharacterPattern::new(&[&[1, 1], &[1, 1]]));
+        font.insert('q', CharacterPattern::new(&[&[1, 1], &[1, 1], &[0, 1]]).with_baseline(2));
+        assert_eq!(font.height(), 3);
+
+        let bitmap = font.render("oq", &RenderOptions::new().padding(0)).unwrap();
+        assert_eq!(bitmap.to_string(), "11011\n11011\n00001\n");
+    }
+
+    #[test]
+    fn test_text_length_limit() {
+        let text = "a".repeat(1001);
+        assert_eq!(
+            text_to_pixel_art(&text),
+            Err(PixelArtError::TextTooLong { length: 1001, limit: 1000 })
+        );
+        assert!(text_to_pixel_art(&text[..1000]).is_ok());
+
+        let options = RenderOptions::new().max_chars(3);
+        let error = render_with_options("abcd", &options).unwrap_err();
+        assert_eq!(error, PixelArtError::TextTooLong { length: 4, limit: 3 });
+        assert_eq!(error.to_string(), "Text too long: 4 characters (max: 3)");
+
+        assert!(render_with_options(&text, &RenderOptions::new().max_chars(None)).is_ok());
+    }
+
+    #[test]
+    fn test_bitmap_size_limits() {
+        // "Hi" renders 8 pixels wide and 7 tall
+        let options = RenderOptions::new().max_width(7);
+        assert_eq!(
+            render_with_options("Hi", &options),
+            Err(PixelArtError::BitmapTooWide { width: 8, limit: 7 })
+        );
+        assert!(render_with_options("Hi", &RenderOptions::new().max_width(8)).is_ok());
+
+        let options = RenderOptions::new().max_area(55);
+        assert_eq!(
+            render_with_options("Hi", &options),
+            Err(PixelArtError::BitmapTooLarge { area: 56, limit: 55 })
+        );
+
+        // Sizes too big to compute are rejected instead of overflowing
+        let options = RenderOptions::new().padding(usize::MAX).max_width(None);
+        assert!(matches!(
+            render_with_options("Hi", &options),
+            Err(PixelArtError::BitmapTooWide { width: usize::MAX, limit: usize::MAX })
+        ));
+        assert!(render_with_options("Hi", &options.wrap_width(20)).is_err());
+        let options = RenderOptions::new().line_spacing(usize::MAX);
+        assert!(matches!(
+            render_with_options("Hi\nHi", &options),
+            Err(PixelArtError::BitmapTooLarge { area: usize::MAX, .. })
+        ));
+        assert!(measure("Hi\nHi", &options).is_err());
+    }
+
+    #[test]
+    fn test_validate_text() {
+        assert!(validate_text("Hello World!\nBye").is_ok());
+
+        let report = validate_text("añb~ü").unwrap_err();
+        let found: Vec<(char, usize)> = report
+            .unsupported()
+            .iter()
+            .map(|unsupported| (unsupported.ch, unsupported.char_index))
+            .collect();
+        assert_eq!(found, vec![('ñ', 1), ('ü', 4)]);
+    }
+
+    #[test]
+    fn test_transliterate_option() {
+        assert!(matches!(
+            render("José"),
+            Err(PixelArtError::UnsupportedCharacter('é'))
+        ));
+
+        let options = RenderOptions::new().transliterate(true);
+        assert_eq!(
+            render_with_options("Zoë Straße", &options),
+            render_with_options("Zoe Strasse", &options)
+        );
+        assert_eq!(wrap_text("Zoë", &options).unwrap().lines, vec!["Zoe"]);
+
+        // Break positions refer to the original text, not the longer transliteration
+        let wrapped = wrap_text("ßßßß ab", &options.clone().wrap_width(36)).unwrap();
+        assert_eq!(wrapped.lines, vec!["ssssssss", "ab"]);
+        assert_eq!(wrapped.breaks[0].char_index, 5);
+        // A break inside a replacement points at the character that was replaced
+        let wrapped = wrap_text("ßßßß ab", &options.clone().wrap_width(30)).unwrap();
+        assert_eq!(wrapped.lines, vec!["sssssss", "s ab"]);
+        assert_eq!(wrapped.breaks[0].char_index, 3);
+
+        let transliterated = transliterate("Zoë");
+        assert_eq!(transliterated.text, "Zoe");
+        assert_eq!(transliterated.transliterations[0].original.ch, 'ë');
+        assert!(validate_text(&transliterated.text).is_ok());
+    }
+
+    #[test]
+    fn test_diacritics_option() {
+        assert!(matches!(
+            render("é"),
+            Err(PixelArtError::UnsupportedCharacter('é'))
+        ));
+
+        let options = RenderOptions::new().padding(0).diacritics(true);
+        let bitmap = render_with_options("é", &options).unwrap();
+        assert_eq!(bitmap.to_string(), "010\n100\n000\n011\n101\n110\n011\n");
+
+        // Every line grows to fit the mark, and plain text is unchanged
+        let lines = render_with_options("e\nñ", &options).unwrap();
+        assert_eq!(lines.height(), 7 + 1 + 7);
+        assert_eq!(
+            render_with_options("e", &options),
+            render_with_options("e", &RenderOptions::new().padding(0))
+        );
+
+        // Composed glyphs win over transliteration
+        let both = options.clone().transliterate(true);
+        assert_eq!(render_with_options("é", &both).unwrap(), bitmap);
+
+        // Cedillas hang below the baseline
+        let cedilla = PixelFont::new().compose('ç').unwrap();
+        assert_eq!(cedilla.baseline(), 5);
+        assert_eq!(cedilla.descent(), 2);
+    }
+
+    #[test]
+    fn test_kerning_tightens_pairs() {
+        let options = RenderOptions::new().padding(0);
+        let mut font = PixelFont::new();
+        let loose = font.render("LT", &options).unwrap();
+
+        font.set_kerning('L', 'T', -2);
+        let kerned = font.render("LT", &options).unwrap();
+        assert_eq!(kerned.width(), loose.width() - 2);
+        // The overlapping columns are combined rather than overwritten
+        assert_eq!(kerned.get(2, 4), Some(true));
+        assert_eq!(kerned.get(2, 0), Some(true));
+
+        // Wrapping measures kerned widths
+        let wrap = RenderOptions::new().padding(0).wrap_width(kerned.width());
+        assert_eq!(font.wrap_text("LT", &wrap).unwrap().lines, vec!["LT"]);
+
+        font.set_kerning('L', 'T', 0);
+        assert!(font.kerning_pairs().is_empty());
+        assert_eq!(font.render("LT", &options).unwrap(), loose);
+    }
 }
@@ -0,0 +1,235 @@
+use crate::diacritic::Composed;
+use crate::layout::GlyphSource;
+use crate::translit::{self, Prepared, Transliteration};
+use crate::validate::UnsupportedChar;
+use crate::{Bitmap, CharacterPattern, PixelArtError, RenderOptions};
+
+/// What lossy rendering draws in place of a character the font cannot render
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Replacement {
+    /// An outlined box as tall as the font's ascent
+    #[default]
+    Tofu,
+    /// The font's glyph for another character, such as `'?'`
+    ///
+    /// Falls back to [`Replacement::Tofu`] if the font lacks that glyph too.
+    Char(char),
+    /// Blank space of the given width in pixels
+    Blank(usize),
+    /// Nothing at all, as if the character was not in the text
+    Skip,
+}
+
+/// A single unsupported character and what was drawn instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Substitution {
+    /// The character that could not be rendered and where it was
+    pub original: UnsupportedChar,
+    /// The replacement actually used for it
+    pub replacement: Replacement,
+}
+
+/// Result of a lossy render: the bitmap plus every substitution made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossyRender {
+    /// The rendered text
+    pub bitmap: Bitmap,
+    /// The unsupported characters in the order they appear in the text
+    pub substitutions: Vec<Substitution>,
+    /// Characters replaced by a fallback spelling before rendering, if
+    /// [`RenderOptions::transliterate`] is set
+    pub transliterations: Vec<Transliteration>,
+}
+
+impl LossyRender {
+    /// Returns true if every character was rendered with its own glyph
+    pub fn is_exact(&self) -> bool {
+        self.substitutions.is_empty() && self.transliterations.is_empty()
+    }
+}
+
+/// Glyphs that draw a fixed replacement pattern for every missing one
+struct LossyGlyphs<'a, G: ?Sized> {
+    glyphs: Composed<'a, G>,
+    fallback: Option<CharacterPattern>,
+}
+
+impl<G: GlyphSource + ?Sized> GlyphSource for LossyGlyphs<'_, G> {
+    fn glyph(&self, ch: char) -> Option<&CharacterPattern> {
+        self.glyphs.glyph(ch).or(self.fallback.as_ref())
+    }
+
+    fn ascent(&self) -> usize {
+        self.glyphs.ascent()
+    }
+
+    fn descent(&self) -> usize {
+        self.glyphs.descent()
+    }
+
+    fn kerning(&self, left: char, right: char) -> isize {
+        self.glyphs.kerning(left, right)
+    }
+}
+
+/// Render text, substituting every unsupported character
+pub(crate) fn render<G: GlyphSource + ?Sized>(
+    font: &G,
+    text: &str,
+    options: &RenderOptions,
+) -> Result<LossyRender, PixelArtError> {
+    crate::check_length(text, options)?;
+
+    let (fallback, replacement) = match options.replacement {
+        Replacement::Tofu => (Some(tofu(font)), Replacement::Tofu),
+        Replacement::Char(ch) => match font.glyph(ch) {
+            Some(pattern) => (Some(pattern.clone()), Replacement::Char(ch)),
+            None => (Some(tofu(font)), Replacement::Tofu),
+        },
+        Replacement::Blank(width) => (Some(blank(width)), Replacement::Blank(width)),
+        Replacement::Skip => (None, Replacement::Skip),
+    };
+    let glyphs = Composed::new(font, text, options.diacritics);
+    let Prepared {
+        text,
+        transliterations,
+        unsupported,
+    } = translit::prepare(
+        text,
+        &glyphs,
+        options.transliterate,
+        replacement == Replacement::Skip,
+    );
+    let substitutions = unsupported
+        .into_iter()
+        .map(|original| Substitution {
+            original,
+            replacement,
+        })
+        .collect();
+
+    let bitmap = if text.is_empty() {
+        Bitmap::default()
+    } else {
+        let glyphs = LossyGlyphs { glyphs, fallback };
+        let wrapped = crate::wrap::wrap(&text, &glyphs, options)?;
+        crate::draw_lines(&wrapped.lines, &glyphs, options)?
+    };
+
+    Ok(LossyRender {
+        bitmap,
+        substitutions,
+        transliterations,
+    })
+}
+
+/// An outlined box filling the rows above the baseline
+fn tofu<G: GlyphSource + ?Sized>(font: &G) -> CharacterPattern {
+    // Fonts with nothing above the baseline get a box in the descent instead
+    let (height, baseline) = if font.ascent() > 0 {
+        (font.ascent(), font.ascent())
+    } else {
+        (font.descent(), 0)
+    };
+    let width = height.div_ceil(2) + 1;
+    let mut bitmap = Bitmap::new(width, height);
+    bitmap.fill(true);
+    bitmap.fill_rect(
+        1,
+        1,
+        width.saturating_sub(2),
+        height.saturating_sub(2),
+        false,
+    );
+    CharacterPattern::from_bitmap(bitmap, baseline)
+}
+
+/// A glyph with no pixels that only advances the cursor
+fn blank(width: usize) -> CharacterPattern {
+    CharacterPattern::from_bitmap(Bitmap::new(width, 0), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PixelFont;
+
+    fn options(replacement: Replacement) -> RenderOptions {
+        RenderOptions::new().padding(0).replacement(replacement)
+    }
+
+    #[test]
+    fn test_tofu_replacement() {
+        let font = PixelFont::new();
+        let result = render(&font, "AéB", &options(Replacement::Tofu)).unwrap();
+        assert_eq!(
+            result.substitutions,
+            vec![Substitution {
+                original: UnsupportedChar {
+                    ch: 'é',
+                    char_index: 1,
+                    byte_offset: 1
+                },
+                replacement: Replacement::Tofu,
+            }]
+        );
+
+        let a = font.get_pattern('A').unwrap().width();
+        let rows: Vec<String> = result
+            .bitmap
+            .to_string()
+            .lines()
+            .map(String::from)
+            .collect();
+        assert_eq!(&rows[0][a + 1..a + 5], "1111");
+        assert_eq!(&rows[2][a + 1..a + 5], "1001");
+        assert_eq!(&rows[4][a + 1..a + 5], "1111");
+    }
+
+    #[test]
+    fn test_char_and_blank_replacement() {
+        let font = PixelFont::new();
+        let question = render(&font, "é", &options(Replacement::Char('?'))).unwrap();
+        assert_eq!(
+            question.bitmap,
+            font.render("?", &options(Replacement::Tofu)).unwrap()
+        );
+
+        // A replacement the font cannot draw either falls back to tofu
+        let missing = render(&font, "é", &options(Replacement::Char('ñ'))).unwrap();
+        assert_eq!(missing.substitutions[0].replacement, Replacement::Tofu);
+
+        let blank = render(&font, "AéA", &options(Replacement::Blank(3))).unwrap();
+        assert_eq!(
+            blank.bitmap,
+            font.render("A  A", &options(Replacement::Tofu).word_spacing(1))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_skip_replacement() {
+        let font = PixelFont::new();
+        let result = render(&font, "AéB\r", &options(Replacement::Skip)).unwrap();
+        assert_eq!(result.substitutions.len(), 2);
+        assert_eq!(
+            result.bitmap,
+            font.render("AB", &RenderOptions::new().padding(0)).unwrap()
+        );
+
+        let only_unsupported = render(&font, "ñ", &options(Replacement::Skip)).unwrap();
+        assert!(only_unsupported.bitmap.is_empty());
+        assert!(!only_unsupported.is_exact());
+    }
+
+    #[test]
+    fn test_transliterates_before_replacing() {
+        let font = PixelFont::new();
+        let options = options(Replacement::Skip).transliterate(true);
+        let result = render(&font, "雪Straße", &options).unwrap();
+        assert_eq!(result.bitmap, font.render("Strasse", &options).unwrap());
+        assert_eq!(result.transliterations.len(), 1);
+        assert_eq!(result.transliterations[0].original.char_index, 5);
+        assert_eq!(result.substitutions[0].original.ch, '雪');
+    }
+}
@@ -1,20 +1,107 @@
-use std::io::{self, Write};
-use text_to_input::{text_to_pixel_art, PixelArtError};
+use std::io::{self, Read, Write};
+use text_to_input::{
+    render, validate_text, ColorMode, PixelArtError, Rgba, TerminalOptions, TerminalStyle,
+    TextFormat,
+};
+
+const USAGE: &str = "Usage: text_to_input \
+                     [--style digits|hash|emoji|blocks|full|half|braille] \
+                     [--color auto|always|never]";
+
+/// How each pixel of the banner is printed
+#[derive(Debug, PartialEq)]
+enum Style {
+    /// Fixed strings per pixel, like the classic '1'/'0' digits
+    Text(TextFormat),
+    /// Block or braille characters, coloured when the terminal allows
+    Terminal(TerminalStyle),
+}
+
+/// How the banner is printed
+struct Args {
+    style: Style,
+    color: ColorMode,
+}
+
+/// Parse `--style` and `--color`, given either as `--name value` or `--name=value`
+///
+/// Text presets and terminal styles share `--style`, so their names must differ.
+fn parse_args<I: IntoIterator<Item = String>>(raw: I) -> Result<Args, String> {
+    let mut args = Args {
+        style: Style::Text(TextFormat::digits()),
+        color: ColorMode::Auto,
+    };
+    let mut raw = raw.into_iter();
+    while let Some(arg) = raw.next() {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None => {
+                let value = raw.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                (arg, value)
+            }
+        };
+        match (name.as_str(), value.as_str()) {
+            ("--style", "full") => args.style = Style::Terminal(TerminalStyle::Blocks),
+            ("--style", "half") => args.style = Style::Terminal(TerminalStyle::HalfBlocks),
+            ("--style", "braille") => args.style = Style::Terminal(TerminalStyle::Braille),
+            ("--color", "auto") => args.color = ColorMode::Auto,
+            ("--color", "always") => args.color = ColorMode::Always,
+            ("--color", "never") => args.color = ColorMode::Never,
+            ("--style", preset) => {
+                let format = TextFormat::preset(preset)
+                    .ok_or_else(|| format!("invalid value for {}: {}", name, value))?;
+                args.style = Style::Text(format);
+            }
+            ("--color", _) => return Err(format!("invalid value for {}: {}", name, value)),
+            _ => return Err(format!("unknown option: {}", name)),
+        }
+    }
+    Ok(args)
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    print!("Enter your text input: ");
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("Error: {}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    
+    print!("Enter your text input (Ctrl-D to finish): ");
     io::stdout().flush()?;
     
+    // Read everything so multi-line banners can be entered
     let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    io::stdin().read_to_string(&mut input)?;
     
-    let text = input.trim();
+    let text = input.trim_end_matches(['\r', '\n']);
     
-    match text_to_pixel_art(text) {
-        Ok(pixel_art) => {
+    // Point out every unsupported character at once
+    if let Err(report) = validate_text(text) {
+        eprintln!("Error: Some characters are not supported by the font.");
+        eprintln!("{}", report);
+        eprintln!("Supported characters: A-Z, a-z, 0-9, and various symbols");
+        std::process::exit(1);
+    }
+    
+    match render(text) {
+        Ok(bitmap) => {
             println!("\noutput:");
-            for line in pixel_art.lines() {
-                println!("{}", line);
+            match &args.style {
+                Style::Terminal(style) => {
+                    let options = TerminalOptions::new()
+                        .style(*style)
+                        .on_color(Rgba::rgb(80, 250, 120))
+                        .color_mode(args.color);
+                    print!("{}", bitmap.to_terminal(&options));
+                }
+                Style::Text(format) => print!("{}", bitmap.format(format)),
             }
         }
         Err(PixelArtError::UnsupportedCharacter(ch)) => {
@@ -22,11 +109,43 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("Supported characters: A-Z, a-z, 0-9, and various symbols");
             std::process::exit(1);
         }
-        Err(PixelArtError::TextTooLong(len)) => {
-            eprintln!("Error: Text is too long ({} characters). Maximum length is 1000 characters.", len);
+        Err(PixelArtError::TextTooLong { length, limit }) => {
+            eprintln!("Error: Text is too long ({} characters). Maximum length is {} characters.", length, limit);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
             std::process::exit(1);
         }
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(value: &str) -> Result<Style, String> {
+        parse_args(["--style".to_string(), value.to_string()]).map(|args| args.style)
+    }
+
+    #[test]
+    fn test_every_style_is_reachable() {
+        for preset in ["digits", "hash", "emoji", "blocks"] {
+            assert_eq!(style(preset), Ok(Style::Text(TextFormat::preset(preset).unwrap())));
+        }
+        assert_eq!(style("full"), Ok(Style::Terminal(TerminalStyle::Blocks)));
+        assert_eq!(style("half"), Ok(Style::Terminal(TerminalStyle::HalfBlocks)));
+        assert_eq!(style("braille"), Ok(Style::Terminal(TerminalStyle::Braille)));
+        assert!(style("stars").is_err());
+    }
+
+    #[test]
+    fn test_color_and_equals_syntax() {
+        let args = parse_args(["--style=half".to_string(), "--color=never".to_string()]).unwrap();
+        assert_eq!(args.style, Style::Terminal(TerminalStyle::HalfBlocks));
+        assert_eq!(args.color, ColorMode::Never);
+        assert!(parse_args(["--color".to_string()]).is_err());
+    }
+}
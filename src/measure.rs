@@ -0,0 +1,99 @@
+use crate::layout::Layout;
+use crate::{PixelArtError, RenderOptions};
+
+/// Where a single character of rendered text is placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharMetrics {
+    /// The character, as laid out after any wrapping or transliteration
+    pub ch: char,
+    /// Index of the output line the character is on
+    pub line: usize,
+    /// Column of the bitmap where the character starts, padding included
+    pub x: usize,
+    /// Number of columns the character itself takes up, spacing excluded
+    pub advance: usize,
+}
+
+/// Size and character positions of text as it would be rendered
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Measurement {
+    /// Width of the bitmap in pixels, padding included
+    pub width: usize,
+    /// Height of the bitmap in pixels, padding included
+    pub height: usize,
+    /// Every character of every line in order, spaces included
+    pub chars: Vec<CharMetrics>,
+}
+
+impl Measurement {
+    /// Returns true if the rendered bitmap would be at most `width` by `height` pixels
+    pub fn fits(&self, width: usize, height: usize) -> bool {
+        self.width <= width && self.height <= height
+    }
+}
+
+/// Describe a layout the way rendering it would place it on the bitmap
+pub(crate) fn measure(
+    layout: &Layout,
+    options: &RenderOptions,
+) -> Result<Measurement, PixelArtError> {
+    let (width, height) = layout.total_size(options)?;
+    let chars = layout
+        .chars
+        .iter()
+        .map(|position| CharMetrics {
+            ch: position.ch,
+            line: position.line,
+            x: options.padding_left + position.x,
+            advance: position.advance,
+        })
+        .collect();
+    Ok(Measurement {
+        width,
+        height,
+        chars,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PixelFont, RenderOptions};
+
+    #[test]
+    fn test_measure_matches_render() {
+        let font = PixelFont::new();
+        for options in [
+            RenderOptions::new(),
+            RenderOptions::new().padding(3).letter_spacing(-1),
+            RenderOptions::new()
+                .wrap_width(20)
+                .alignment(crate::Alignment::Center),
+        ] {
+            let text = "Hello World\nfit me";
+            let measurement = font.measure(text, &options).unwrap();
+            let bitmap = font.render(text, &options).unwrap();
+            assert_eq!(
+                (measurement.width, measurement.height),
+                (bitmap.width(), bitmap.height())
+            );
+        }
+    }
+
+    #[test]
+    fn test_char_offsets() {
+        let font = PixelFont::new();
+        let measurement = font.measure("Hi y", &RenderOptions::new()).unwrap();
+        let offsets: Vec<(char, usize, usize)> = measurement
+            .chars
+            .iter()
+            .map(|metrics| (metrics.ch, metrics.x, metrics.advance))
+            .collect();
+        // 'H' is 4 wide, 'i' 1 wide and the space uses the word spacing
+        assert_eq!(
+            offsets,
+            vec![('H', 1, 4), ('i', 6, 1), (' ', 8, 2), ('y', 11, 3)]
+        );
+        assert!(measurement.fits(measurement.width, 7));
+        assert!(!measurement.fits(measurement.width - 1, 7));
+    }
+}
@@ -0,0 +1,327 @@
+//! Netpbm export (PBM, PGM and PPM) and PBM import
+//!
+//! PBM stores the bitmap as it is, one image pixel per bitmap pixel with 1
+//! meaning black. PGM and PPM go through [`ImageOptions`] like PNG does, so
+//! they support scaling, grid gaps and colours; as neither format has an alpha
+//! channel, translucent colours are blended over white.
+
+use std::io::{self, Read, Write};
+
+use crate::image::{self, ImageOptions};
+use crate::{Bitmap, Rgba};
+
+/// Longest line plain Netpbm files should have
+const MAX_LINE: usize = 70;
+
+/// How the pixels of a Netpbm image are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetpbmEncoding {
+    /// ASCII numbers (P1, P2 and P3), readable and diff-friendly
+    Plain,
+    /// Binary data (P4, P5 and P6), much smaller
+    #[default]
+    Raw,
+}
+
+/// Write `bitmap` as a PBM image, P1 or P4
+pub(crate) fn write_pbm<W: Write>(
+    bitmap: &Bitmap,
+    encoding: NetpbmEncoding,
+    mut writer: W,
+) -> io::Result<()> {
+    let (width, height) = (bitmap.width(), bitmap.height());
+    let mut out = Vec::new();
+    match encoding {
+        NetpbmEncoding::Plain => {
+            out.extend_from_slice(format!("P1\n{} {}\n", width, height).as_bytes());
+            for row in bitmap.rows() {
+                let digits: Vec<u8> = row.map(|pixel| if pixel { b'1' } else { b'0' }).collect();
+                for line in digits.chunks(MAX_LINE) {
+                    out.extend_from_slice(line);
+                    out.push(b'\n');
+                }
+            }
+        }
+        NetpbmEncoding::Raw => {
+            out.extend_from_slice(format!("P4\n{} {}\n", width, height).as_bytes());
+            let row_bytes = width.div_ceil(8);
+            for row in bitmap.rows() {
+                let start = out.len();
+                out.resize(start + row_bytes, 0);
+                for (x, _) in row.enumerate().filter(|&(_, pixel)| pixel) {
+                    out[start + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+    }
+    writer.write_all(&out)
+}
+
+/// Write `bitmap` as a greyscale PGM image, P2 or P5
+pub(crate) fn write_pgm<W: Write>(
+    bitmap: &Bitmap,
+    options: &ImageOptions,
+    encoding: NetpbmEncoding,
+    writer: W,
+) -> io::Result<()> {
+    let palette = options.palette().map(|color| vec![grey(color)]);
+    write_graymap(bitmap, options, encoding, ["P2", "P5"], &palette, writer)
+}
+
+/// Write `bitmap` as a colour PPM image, P3 or P6
+pub(crate) fn write_ppm<W: Write>(
+    bitmap: &Bitmap,
+    options: &ImageOptions,
+    encoding: NetpbmEncoding,
+    writer: W,
+) -> io::Result<()> {
+    let palette = options.palette().map(|color| {
+        let color = over_white(color);
+        vec![color.r, color.g, color.b]
+    });
+    write_graymap(bitmap, options, encoding, ["P3", "P6"], &palette, writer)
+}
+
+/// Write an image with a maximum value of 255, each pixel given by its palette entry
+fn write_graymap<W: Write>(
+    bitmap: &Bitmap,
+    options: &ImageOptions,
+    encoding: NetpbmEncoding,
+    [plain_magic, raw_magic]: [&str; 2],
+    palette: &[Vec<u8>; 3],
+    mut writer: W,
+) -> io::Result<()> {
+    let (width, height) = options.image_size(bitmap)?;
+    let magic = match encoding {
+        NetpbmEncoding::Plain => plain_magic,
+        NetpbmEncoding::Raw => raw_magic,
+    };
+    let mut out = format!("{}\n{} {}\n255\n", magic, width, height).into_bytes();
+    for row in image::rows(bitmap, options, (width, height)) {
+        let samples = row
+            .into_iter()
+            .flat_map(|pixel| palette[pixel as usize].iter().copied());
+        match encoding {
+            NetpbmEncoding::Raw => out.extend(samples),
+            NetpbmEncoding::Plain => {
+                let mut line = String::new();
+                for sample in samples {
+                    let sample = sample.to_string();
+                    if !line.is_empty() && line.len() + 1 + sample.len() > MAX_LINE {
+                        out.extend_from_slice(line.as_bytes());
+                        out.push(b'\n');
+                        line.clear();
+                    }
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(&sample);
+                }
+                out.extend_from_slice(line.as_bytes());
+                out.push(b'\n');
+            }
+        }
+    }
+    writer.write_all(&out)
+}
+
+/// `color` blended over a white background
+fn over_white(color: Rgba) -> Rgba {
+    let blend = |channel: u8| {
+        let alpha = u32::from(color.a);
+        ((u32::from(channel) * alpha + 255 * (255 - alpha) + 127) / 255) as u8
+    };
+    Rgba::rgb(blend(color.r), blend(color.g), blend(color.b))
+}
+
+/// Perceived brightness of `color` blended over white (ITU-R BT.601 weights)
+fn grey(color: Rgba) -> u8 {
+    let color = over_white(color);
+    let luma = 299 * u32::from(color.r) + 587 * u32::from(color.g) + 114 * u32::from(color.b);
+    ((luma + 500) / 1000) as u8
+}
+
+/// Reads the header fields of a Netpbm file, skipping whitespace and comments
+struct Header<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Header<'_> {
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.data.get(self.position) {
+            match byte {
+                b'#' => {
+                    while self.data.get(self.position).is_some_and(|&b| b != b'\n') {
+                        self.position += 1;
+                    }
+                }
+                _ if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn number(&mut self, name: &str) -> io::Result<usize> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self.data.get(self.position).is_some_and(u8::is_ascii_digit) {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| invalid_data(&format!("PBM {} is missing or invalid", name)))
+    }
+}
+
+/// Read a plain (P1) or raw (P4) PBM image
+pub(crate) fn read_pbm<R: Read>(mut reader: R) -> io::Result<Bitmap> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let raw = match data.get(..2) {
+        Some(b"P1") => false,
+        Some(b"P4") => true,
+        _ => return Err(invalid_data("not a PBM image (expected P1 or P4)")),
+    };
+
+    let mut header = Header {
+        data: &data,
+        position: 2,
+    };
+    let width = header.number("width")?;
+    let height = header.number("height")?;
+    let pixels = width
+        .checked_mul(height)
+        .ok_or_else(|| invalid_data("PBM image is too large"))?;
+
+    if raw {
+        // Exactly one whitespace byte separates the header from the pixels
+        let start = header.position + 1;
+        let row_bytes = width.div_ceil(8);
+        let raster = data
+            .get(start..)
+            .filter(|raster| raster.len() >= row_bytes * height)
+            .ok_or_else(|| invalid_data("PBM image data is truncated"))?;
+        let mut bitmap = Bitmap::new(width, height);
+        for (y, row) in raster.chunks(row_bytes.max(1)).take(height).enumerate() {
+            for x in 0..width {
+                if row[x / 8] & (0x80 >> (x % 8)) != 0 {
+                    bitmap.set(x, y, true);
+                }
+            }
+        }
+        Ok(bitmap)
+    } else {
+        // Every pixel takes at least one byte, so this also bounds the allocation
+        if data.len() - header.position < pixels {
+            return Err(invalid_data("PBM image data is truncated"));
+        }
+        let mut bitmap = Bitmap::new(width, height);
+        let mut index = 0;
+        while index < pixels {
+            header.skip_whitespace();
+            let value = match data.get(header.position) {
+                Some(b'0') => false,
+                Some(b'1') => true,
+                Some(_) => return Err(invalid_data("PBM pixels must be 0 or 1")),
+                None => return Err(invalid_data("PBM image data is truncated")),
+            };
+            header.position += 1;
+            if value {
+                bitmap.set(index % width, index / width, true);
+            }
+            index += 1;
+        }
+        Ok(bitmap)
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Bitmap {
+        let mut bitmap = Bitmap::new(10, 2);
+        bitmap.set(0, 0, true);
+        bitmap.set(9, 1, true);
+        bitmap
+    }
+
+    fn pbm(bitmap: &Bitmap, encoding: NetpbmEncoding) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_pbm(bitmap, encoding, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_pbm_output() {
+        let plain = pbm(&sample(), NetpbmEncoding::Plain);
+        assert_eq!(plain, b"P1\n10 2\n1000000000\n0000000001\n");
+
+        let raw = pbm(&sample(), NetpbmEncoding::Raw);
+        assert_eq!(raw, b"P4\n10 2\n\x80\x00\x00\x40");
+
+        let wide = pbm(&Bitmap::new(100, 1), NetpbmEncoding::Plain);
+        let text = String::from_utf8(wide).unwrap();
+        assert!(text.lines().all(|line| line.len() <= MAX_LINE));
+    }
+
+    #[test]
+    fn test_pbm_round_trip() {
+        for encoding in [NetpbmEncoding::Plain, NetpbmEncoding::Raw] {
+            let bitmap = sample();
+            assert_eq!(read_pbm(&pbm(&bitmap, encoding)[..]).unwrap(), bitmap);
+        }
+        // Comments, spread-out whitespace and digits without separators are all allowed
+        let text = b"P1 # a comment\n# another\n 3\t2\n1 0 1\n010";
+        let bitmap = read_pbm(&text[..]).unwrap();
+        assert_eq!(bitmap.to_string(), "101\n010\n");
+    }
+
+    #[test]
+    fn test_pbm_errors() {
+        for bad in [
+            &b"P2\n1 1\n1"[..],
+            b"P1\n2\n",
+            b"P1\n2 2\n101",
+            b"P1\n1 1\n7",
+            b"P4\n9 2\n\x00\x00\x00",
+        ] {
+            let error = read_pbm(bad).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_pgm_and_ppm() {
+        let options = ImageOptions::new()
+            .scale(2)
+            .on_color(Rgba::rgb(255, 0, 0))
+            .off_color(Rgba::TRANSPARENT);
+        let mut bitmap = Bitmap::new(2, 1);
+        bitmap.set(0, 0, true);
+
+        let mut pgm = Vec::new();
+        write_pgm(&bitmap, &options, NetpbmEncoding::Plain, &mut pgm).unwrap();
+        assert_eq!(pgm, b"P2\n4 2\n255\n76 76 255 255\n76 76 255 255\n");
+
+        let mut ppm = Vec::new();
+        write_ppm(&bitmap, &options, NetpbmEncoding::Raw, &mut ppm).unwrap();
+        let (header, pixels) = ppm.split_at(11);
+        assert_eq!(header, b"P6\n4 2\n255\n");
+        assert_eq!(pixels.len(), 4 * 2 * 3);
+        assert_eq!(&pixels[..6], &[255, 0, 0, 255, 0, 0]);
+        assert_eq!(&pixels[6..9], &[255, 255, 255]);
+
+        let huge = ImageOptions::new().scale(usize::MAX / 2);
+        let error = write_pgm(&bitmap, &huge, NetpbmEncoding::Raw, Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let error = write_ppm(&bitmap, &huge, NetpbmEncoding::Plain, Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::Replacement;
+
+/// Horizontal alignment of lines shorter than the widest line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Flush against the left edge
+    #[default]
+    Left,
+    /// Centered, leaning left when the leftover space is odd
+    Center,
+    /// Flush against the right edge
+    Right,
+}
+
+/// Layout settings used when rendering text
+///
+/// Built with chained setters starting from [`RenderOptions::new`]; the
+/// defaults reproduce the classic output of [`crate::text_to_pixel_art`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub(crate) padding_top: usize,
+    pub(crate) padding_bottom: usize,
+    pub(crate) padding_left: usize,
+    pub(crate) padding_right: usize,
+    pub(crate) padding_fill: bool,
+    pub(crate) letter_spacing: isize,
+    pub(crate) word_spacing: usize,
+    pub(crate) line_spacing: usize,
+    pub(crate) alignment: Alignment,
+    pub(crate) wrap_width: Option<usize>,
+    pub(crate) hyphenate: bool,
+    pub(crate) max_chars: Option<usize>,
+    pub(crate) max_width: Option<usize>,
+    pub(crate) max_area: Option<usize>,
+    pub(crate) replacement: Replacement,
+    pub(crate) transliterate: bool,
+    pub(crate) diacritics: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            padding_top: 1,
+            padding_bottom: 1,
+            padding_left: 1,
+            padding_right: 1,
+            padding_fill: false,
+            letter_spacing: 1,
+            word_spacing: 2,
+            line_spacing: 1,
+            alignment: Alignment::Left,
+            wrap_width: None,
+            hyphenate: false,
+            max_chars: Some(1000),
+            max_width: None,
+            max_area: None,
+            replacement: Replacement::Tofu,
+            transliterate: false,
+            diacritics: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Create options with the default layout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the padding on all four sides
+    pub fn padding(self, padding: usize) -> Self {
+        self.padding_top(padding)
+            .padding_bottom(padding)
+            .padding_left(padding)
+            .padding_right(padding)
+    }
+
+    /// Set the number of padding rows above the text
+    pub fn padding_top(mut self, rows: usize) -> Self {
+        self.padding_top = rows;
+        self
+    }
+
+    /// Set the number of padding rows below the text
+    pub fn padding_bottom(mut self, rows: usize) -> Self {
+        self.padding_bottom = rows;
+        self
+    }
+
+    /// Set the number of padding columns left of the text
+    pub fn padding_left(mut self, columns: usize) -> Self {
+        self.padding_left = columns;
+        self
+    }
+
+    /// Set the number of padding columns right of the text
+    pub fn padding_right(mut self, columns: usize) -> Self {
+        self.padding_right = columns;
+        self
+    }
+
+    /// Set whether padding pixels are drawn lit instead of blank
+    pub fn padding_fill(mut self, lit: bool) -> Self {
+        self.padding_fill = lit;
+        self
+    }
+
+    /// Set the number of blank columns between characters
+    ///
+    /// Negative values make neighbouring glyphs overlap; overlapping pixels
+    /// are combined so a lit pixel is never erased.
+    pub fn letter_spacing(mut self, columns: isize) -> Self {
+        self.letter_spacing = columns;
+        self
+    }
+
+    /// Set the width of a space character in columns
+    pub fn word_spacing(mut self, columns: usize) -> Self {
+        self.word_spacing = columns;
+        self
+    }
+
+    /// Set the number of blank rows between lines of text
+    pub fn line_spacing(mut self, rows: usize) -> Self {
+        self.line_spacing = rows;
+        self
+    }
+
+    /// Set how lines shorter than the widest line are aligned
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Wrap text so the rendered bitmap is at most `columns` pixels wide
+    ///
+    /// The width includes the left and right padding. Lines are broken at
+    /// spaces or after hyphens where possible and inside words otherwise.
+    pub fn wrap_width(mut self, columns: usize) -> Self {
+        self.wrap_width = Some(columns);
+        self
+    }
+
+    /// Set whether words split across lines end with a hyphen glyph
+    pub fn hyphenate(mut self, hyphenate: bool) -> Self {
+        self.hyphenate = hyphenate;
+        self
+    }
+
+    /// Limit the number of characters that may be rendered, or `None` for no limit
+    ///
+    /// Defaults to 1000 characters.
+    pub fn max_chars(mut self, limit: impl Into<Option<usize>>) -> Self {
+        self.max_chars = limit.into();
+        self
+    }
+
+    /// Limit the width of the rendered bitmap in pixels, or `None` for no limit
+    ///
+    /// Unlike [`RenderOptions::wrap_width`] this never changes the layout; text
+    /// that would be wider fails to render. There is no limit by default.
+    pub fn max_width(mut self, limit: impl Into<Option<usize>>) -> Self {
+        self.max_width = limit.into();
+        self
+    }
+
+    /// Limit the total number of pixels in the rendered bitmap, or `None` for no limit
+    ///
+    /// There is no limit by default.
+    pub fn max_area(mut self, limit: impl Into<Option<usize>>) -> Self {
+        self.max_area = limit.into();
+        self
+    }
+
+    /// Set what lossy rendering draws in place of unsupported characters
+    ///
+    /// Only used by [`crate::PixelFont::render_lossy`]; defaults to a tofu box.
+    pub fn replacement(mut self, replacement: Replacement) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Set whether unsupported characters are first replaced by a fallback
+    /// spelling the font can draw, such as `é` → `e` or `ß` → `ss`
+    ///
+    /// Off by default. See [`crate::PixelFont::transliterate`].
+    pub fn transliterate(mut self, transliterate: bool) -> Self {
+        self.transliterate = transliterate;
+        self
+    }
+
+    /// Set whether accented letters the font lacks are drawn by combining the
+    /// base letter with a [`crate::Mark`], such as `é` from `e` and an acute
+    ///
+    /// Lines grow taller when a mark does not fit in the font's spare rows.
+    /// Composed glyphs are preferred over transliteration. Off by default.
+    pub fn diacritics(mut self, diacritics: bool) -> Self {
+        self.diacritics = diacritics;
+        self
+    }
+}
@@ -0,0 +1,396 @@
+//! PNG export
+//!
+//! Images are written as 8-bit palette PNGs, with a transparency chunk when
+//! any colour is not fully opaque. The image data is compressed with a small
+//! deflate encoder that only looks for repeats of the previous byte and of the
+//! previous row, which is all a scaled-up bitmap needs to shrink well.
+
+use std::io::{self, Write};
+
+use crate::image::{self, ImageOptions};
+use crate::Bitmap;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+const COLOR_TYPE_PALETTE: u8 = 3;
+
+/// Write `bitmap` as a PNG image
+pub(crate) fn write_png<W: Write>(
+    bitmap: &Bitmap,
+    options: &ImageOptions,
+    mut writer: W,
+) -> io::Result<()> {
+    let (width, height) = options.image_size(bitmap)?;
+    if width == 0 || height == 0 {
+        return Err(invalid_input("PNG images must have at least one pixel"));
+    }
+    let (Some(png_width), Some(png_height)) = (png_dimension(width), png_dimension(height)) else {
+        return Err(invalid_input(
+            "PNG images are at most 2^31 - 1 pixels across",
+        ));
+    };
+    // Each row is prefixed with filter type 0 (none)
+    let data_len = (width + 1)
+        .checked_mul(height)
+        .ok_or_else(|| invalid_input("PNG image is too large"))?;
+
+    let mut data = Vec::with_capacity(data_len);
+    for row in image::rows(bitmap, options, (width, height)) {
+        data.push(0);
+        data.extend(row.into_iter().map(|pixel| pixel as u8));
+    }
+
+    let palette = options.palette();
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&png_width.to_be_bytes());
+    header.extend_from_slice(&png_height.to_be_bytes());
+    // Bit depth, colour type, compression, filter and interlace methods
+    header.extend_from_slice(&[8, COLOR_TYPE_PALETTE, 0, 0, 0]);
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut out, b"IHDR", &header);
+    let colors: Vec<u8> = palette.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+    write_chunk(&mut out, b"PLTE", &colors);
+    if palette.iter().any(|color| !color.is_opaque()) {
+        let alpha: Vec<u8> = palette.iter().map(|color| color.a).collect();
+        write_chunk(&mut out, b"tRNS", &alpha);
+    }
+    write_chunk(&mut out, b"IDAT", &zlib(&data, width + 1));
+    write_chunk(&mut out, b"IEND", &[]);
+    writer.write_all(&out)
+}
+
+/// `size` as a PNG width or height, which must fit in 31 bits
+fn png_dimension(size: usize) -> Option<u32> {
+    u32::try_from(size)
+        .ok()
+        .filter(|&size| size <= i32::MAX as u32)
+}
+
+/// Append a chunk with its length and checksum
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// CRC-32 as used by PNG chunks (reflected, polynomial 0xEDB88320)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Adler-32 checksum of the uncompressed data, as used by zlib
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most that can be summed before `b` could overflow
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// Writes bits least significant first, as deflate expects
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            bits: 0,
+            count: 0,
+        }
+    }
+
+    /// Write the low `count` bits of `value`
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= u64::from(value) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code, which deflate stores most significant bit first
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.bits as u8);
+        }
+        self.bytes
+    }
+}
+
+/// First match length of each length code from 257, and its extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// First distance of each distance code, and its extra bits
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_DISTANCE: usize = 32768;
+
+/// Write a literal byte or length symbol with the fixed Huffman code
+fn write_symbol(out: &mut BitWriter, symbol: u16) {
+    let symbol = u32::from(symbol);
+    match symbol {
+        0..=143 => out.write_code(0x30 + symbol, 8),
+        144..=255 => out.write_code(0x190 + symbol - 144, 9),
+        256..=279 => out.write_code(symbol - 256, 7),
+        _ => out.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+/// Index of the last table entry not above `value`
+fn code_index(table: &[u16], value: usize) -> usize {
+    table
+        .iter()
+        .rposition(|&base| usize::from(base) <= value)
+        .unwrap_or(0)
+}
+
+/// Write a back-reference of `length` bytes starting `distance` bytes back
+fn write_match(out: &mut BitWriter, length: usize, distance: usize) {
+    let index = code_index(&LENGTH_BASE, length);
+    write_symbol(out, 257 + index as u16);
+    out.write(
+        (length - usize::from(LENGTH_BASE[index])) as u32,
+        u32::from(LENGTH_EXTRA[index]),
+    );
+
+    let index = code_index(&DISTANCE_BASE, distance);
+    out.write_code(index as u32, 5);
+    out.write(
+        (distance - usize::from(DISTANCE_BASE[index])) as u32,
+        u32::from(DISTANCE_EXTRA[index]),
+    );
+}
+
+/// Number of bytes from `position` that repeat the bytes `distance` earlier
+fn match_length(data: &[u8], position: usize, distance: usize) -> usize {
+    if distance == 0 || distance > position || distance > MAX_DISTANCE {
+        return 0;
+    }
+    data[position..]
+        .iter()
+        .zip(&data[position - distance..])
+        .take(MAX_MATCH)
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Compress `data` as a single fixed-Huffman deflate block
+///
+/// Only repeats of the previous byte and of the byte `row_length` back are
+/// looked for.
+fn deflate(data: &[u8], row_length: usize) -> Vec<u8> {
+    let mut out = BitWriter::new();
+    // Final block, fixed Huffman codes
+    out.write(1, 1);
+    out.write(1, 2);
+
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = [row_length, 1]
+            .into_iter()
+            .map(|distance| (match_length(data, position, distance), distance))
+            .max_by_key(|&(length, _)| length)
+            .unwrap_or((0, 1));
+        if length >= MIN_MATCH {
+            write_match(&mut out, length, distance);
+            position += length;
+        } else {
+            write_symbol(&mut out, u16::from(data[position]));
+            position += 1;
+        }
+    }
+    write_symbol(&mut out, 256);
+    out.finish()
+}
+
+/// Wrap deflated `data` in a zlib stream
+fn zlib(data: &[u8], row_length: usize) -> Vec<u8> {
+    // Deflate with a 32K window, no preset dictionary; the header is a multiple of 31
+    let mut out = vec![0x78, 0x01];
+    out.extend(deflate(data, row_length));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rgba;
+
+    /// Undo `deflate` so tests can check the round trip without a PNG decoder
+    fn inflate_fixed(data: &[u8]) -> Vec<u8> {
+        let mut bit = 0;
+        let mut read = |count: u32| {
+            let mut value = 0;
+            for i in 0..count {
+                value |= u32::from(data[bit / 8] >> (bit % 8) & 1) << i;
+                bit += 1;
+            }
+            value
+        };
+        assert_eq!(read(3), 0b011);
+
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            // Read a fixed Huffman code one bit at a time, most significant first
+            let mut code = 0;
+            let mut length = 0;
+            let symbol = loop {
+                code = (code << 1) | read(1);
+                length += 1;
+                match (length, code) {
+                    (7, 0..=0x17) => break code + 256,
+                    (8, 0x30..=0xBF) => break code - 0x30,
+                    (8, 0xC0..=0xC7) => break code - 0xC0 + 280,
+                    (9, 0x190..=0x1FF) => break code - 0x190 + 144,
+                    _ => {}
+                }
+            };
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => return out,
+                _ => {
+                    let index = (symbol - 257) as usize;
+                    let length = usize::from(LENGTH_BASE[index])
+                        + read(u32::from(LENGTH_EXTRA[index])) as usize;
+                    let index = (0..5).fold(0, |acc, _| (acc << 1) | read(1)) as usize;
+                    let distance = usize::from(DISTANCE_BASE[index])
+                        + read(u32::from(DISTANCE_EXTRA[index])) as usize;
+                    for _ in 0..length {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(&[]), 1);
+    }
+
+    #[test]
+    fn test_deflate_round_trip() {
+        let mut data = Vec::new();
+        for row in 0..40u8 {
+            data.push(0);
+            data.extend((0..300).map(|x| u8::from(x / 7 % 2 == 0) + row / 10));
+        }
+        let compressed = deflate(&data, 301);
+        assert!(compressed.len() < data.len() / 10);
+        assert_eq!(inflate_fixed(&compressed), data);
+
+        let text = b"abcabcabc no repeats here, but literals of every kind \xff\x90";
+        assert_eq!(inflate_fixed(&deflate(text, 3)), text);
+    }
+
+    #[test]
+    fn test_png_layout() {
+        let mut bitmap = Bitmap::new(3, 2);
+        bitmap.set(1, 0, true);
+        let options = ImageOptions::new()
+            .scale(4)
+            .gap(1)
+            .off_color(Rgba::TRANSPARENT)
+            .on_color(Rgba::rgb(255, 0, 0));
+        let mut png = Vec::new();
+        write_png(&bitmap, &options, &mut png).unwrap();
+
+        assert_eq!(png[..8], SIGNATURE);
+        // IHDR: 3 cells of 4 plus 2 gaps wide, 2 cells plus 1 gap tall
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 14, 0, 0, 0, 9]);
+        assert_eq!(&png[37..41], b"PLTE");
+        assert_eq!(&png[41..44], &[0, 0, 0]);
+        assert_eq!(&png[44..47], &[255, 0, 0]);
+        let chunk = |kind: &[u8]| png.windows(4).position(|window| window == kind);
+        assert!(chunk(b"tRNS").is_some());
+        assert_eq!(chunk(b"IEND").unwrap() + 8, png.len());
+
+        // Decompress the image data and check a lit and an unlit row
+        let idat = chunk(b"IDAT").unwrap();
+        let length = u32::from_be_bytes(png[idat - 4..idat].try_into().unwrap()) as usize;
+        let stream = &png[idat + 4..idat + 4 + length];
+        let data = inflate_fixed(&stream[2..stream.len() - 4]);
+        assert_eq!(data.len(), 9 * 15);
+        assert_eq!(&data[..15], &[0, 0, 0, 0, 0, 2, 1, 1, 1, 1, 2, 0, 0, 0, 0]);
+        let gap_row: Vec<u8> = std::iter::once(0).chain([2; 14]).collect();
+        assert_eq!(&data[4 * 15..5 * 15], &gap_row[..]);
+    }
+
+    #[test]
+    fn test_empty_bitmap_is_rejected() {
+        let error = write_png(&Bitmap::new(0, 3), &ImageOptions::new(), Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_oversized_images_are_rejected() {
+        let bitmap = Bitmap::new(2, 1);
+        for options in [
+            ImageOptions::new().scale(usize::MAX / 2),
+            ImageOptions::new().gap(usize::MAX),
+            // Fits in memory arithmetic, but not in a PNG header
+            ImageOptions::new().scale(1 << 31),
+        ] {
+            let error = write_png(&bitmap, &options, Vec::new()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+
+        // A gap between cells that do not exist is never drawn
+        let options = ImageOptions::new().gap(usize::MAX);
+        assert!(write_png(&Bitmap::new(1, 1), &options, Vec::new()).is_ok());
+    }
+}
@@ -0,0 +1,394 @@
+//! Import and export of PC Screen Font (PSF1 and PSF2) console fonts
+//!
+//! Console fonts are fixed-width, so every imported glyph is as wide as the
+//! font cell. Like BDF fonts, the cell normally includes the gap between
+//! characters, so a letter spacing of 0 matches the console look.
+
+use std::io::{self, Read, Write};
+
+use crate::{CharacterPattern, FontError, PixelFont};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE512: u8 = 0x01;
+const PSF1_MODEHASTAB: u8 = 0x02;
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_STARTSEQ: u16 = 0xFFFE;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+const PSF2_HEADER_SIZE: usize = 32;
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_STARTSEQ: u8 = 0xFE;
+
+/// Glyph bitmaps and the characters each one maps to
+struct RawFont {
+    height: usize,
+    glyphs: Vec<Vec<Vec<u8>>>,
+    mapping: Vec<Vec<char>>,
+}
+
+/// Parse a PSF1 or PSF2 font, detecting the version from its magic bytes
+pub(crate) fn parse<R: Read>(mut reader: R) -> Result<PixelFont, FontError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let raw = if data.starts_with(&PSF2_MAGIC) {
+        parse_psf2(&data)?
+    } else if data.starts_with(&PSF1_MAGIC) {
+        parse_psf1(&data)?
+    } else {
+        return Err(invalid("not a PSF font"));
+    };
+
+    let mut font = PixelFont::with_metrics(raw.height, 0);
+    for (pixels, chars) in raw.glyphs.into_iter().zip(raw.mapping) {
+        for ch in chars {
+            // Spaces are laid out using the word spacing instead of a glyph
+            if ch == ' ' || ch.is_control() {
+                continue;
+            }
+            font.insert(ch, CharacterPattern::from_pixels(pixels.clone()));
+        }
+    }
+    Ok(font)
+}
+
+fn parse_psf1(data: &[u8]) -> Result<RawFont, FontError> {
+    let (mode, height) = match data {
+        [_, _, mode, height, ..] => (*mode, *height as usize),
+        _ => return Err(invalid("truncated PSF1 header")),
+    };
+    if height == 0 {
+        return Err(invalid("PSF1 glyph height is zero"));
+    }
+    let count = if mode & PSF1_MODE512 != 0 { 512 } else { 256 };
+    let glyph_data = data
+        .get(4..4 + count * height)
+        .ok_or_else(|| invalid("truncated PSF1 glyph data"))?;
+    let glyphs = decode_glyphs(glyph_data, count, 8, height);
+
+    let mapping = if mode & PSF1_MODEHASTAB != 0 {
+        let table = &data[4 + count * height..];
+        let entries: Vec<u16> = table
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let mut mapping = vec![Vec::new(); count];
+        let mut glyph = 0;
+        let mut in_sequence = false;
+        for entry in entries {
+            if glyph >= count {
+                break;
+            }
+            match entry {
+                PSF1_SEPARATOR => {
+                    glyph += 1;
+                    in_sequence = false;
+                }
+                PSF1_STARTSEQ => in_sequence = true,
+                code if !in_sequence => {
+                    if let Some(ch) = char::from_u32(code as u32) {
+                        mapping[glyph].push(ch);
+                    }
+                }
+                _ => {}
+            }
+        }
+        mapping
+    } else {
+        identity_mapping(count)
+    };
+
+    Ok(RawFont {
+        height,
+        glyphs,
+        mapping,
+    })
+}
+
+fn parse_psf2(data: &[u8]) -> Result<RawFont, FontError> {
+    let field = |index: usize| {
+        data.get(4 + index * 4..8 + index * 4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+            .ok_or_else(|| invalid("truncated PSF2 header"))
+    };
+    let header_size = field(1)?;
+    let flags = field(2)? as u32;
+    let count = field(3)?;
+    let glyph_size = field(4)?;
+    let height = field(5)?;
+    let width = field(6)?;
+
+    if width == 0 || height == 0 {
+        return Err(invalid("PSF2 glyph size is zero"));
+    }
+    if glyph_size < height * width.div_ceil(8) {
+        return Err(invalid("PSF2 glyph size is too small for its dimensions"));
+    }
+    let glyph_end = count
+        .checked_mul(glyph_size)
+        .and_then(|size| size.checked_add(header_size))
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| invalid("truncated PSF2 glyph data"))?;
+    let glyph_data = &data[header_size..glyph_end];
+    let glyphs = glyph_data
+        .chunks_exact(glyph_size)
+        .map(|glyph| decode_glyphs(glyph, 1, width, height).remove(0))
+        .collect();
+
+    let mapping = if flags & PSF2_HAS_UNICODE_TABLE != 0 {
+        let mut mapping = vec![Vec::new(); count];
+        let entries = data[glyph_end..].split(|&byte| byte == PSF2_SEPARATOR);
+        for (chars, entry) in mapping.iter_mut().zip(entries) {
+            // Multi-character sequences follow the single characters and are skipped
+            let singles = entry
+                .split(|&byte| byte == PSF2_STARTSEQ)
+                .next()
+                .unwrap_or(&[]);
+            let text = std::str::from_utf8(singles)
+                .map_err(|_| invalid("invalid UTF-8 in PSF2 unicode table"))?;
+            chars.extend(text.chars());
+        }
+        mapping
+    } else {
+        identity_mapping(count)
+    };
+
+    Ok(RawFont {
+        height,
+        glyphs,
+        mapping,
+    })
+}
+
+/// Map glyph `i` to the character with code point `i`
+fn identity_mapping(count: usize) -> Vec<Vec<char>> {
+    (0..count as u32)
+        .map(|code| char::from_u32(code).into_iter().collect())
+        .collect()
+}
+
+/// Decode `count` consecutive glyphs whose rows are padded to whole bytes
+fn decode_glyphs(data: &[u8], count: usize, width: usize, height: usize) -> Vec<Vec<Vec<u8>>> {
+    let row_bytes = width.div_ceil(8);
+    data.chunks(row_bytes * height)
+        .take(count)
+        .map(|glyph| {
+            glyph
+                .chunks(row_bytes)
+                .map(|row| {
+                    (0..width)
+                        .map(|x| (row[x / 8] >> (7 - x % 8)) & 1)
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Encode a glyph as rows padded to whole bytes, left aligned in the cell
+fn encode_glyph(
+    font: &PixelFont,
+    pattern: Option<&CharacterPattern>,
+    width: usize,
+    out: &mut Vec<u8>,
+) {
+    let row_bytes = width.div_ceil(8);
+    for y in 0..font.height() {
+        let mut row = vec![0u8; row_bytes];
+        if let Some(pixels) = pattern.and_then(|pattern| font.cell_row(pattern, y)) {
+            for (x, _) in pixels.enumerate().filter(|&(_, pixel)| pixel) {
+                row[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        out.extend_from_slice(&row);
+    }
+}
+
+/// Characters to export, with a blank glyph for the space character
+fn export_chars(font: &PixelFont) -> Vec<char> {
+    let mut chars = font.supported_characters();
+    if !chars.contains(&' ') {
+        chars.insert(0, ' ');
+    }
+    chars
+}
+
+/// Write a font as PSF2 with a unicode table
+pub(crate) fn write_psf2<W: Write>(font: &PixelFont, mut writer: W) -> io::Result<()> {
+    let chars = export_chars(font);
+    let width = font
+        .characters
+        .values()
+        .map(CharacterPattern::width)
+        .max()
+        .unwrap_or(1);
+    let height = font.height();
+    let glyph_size = height * width.div_ceil(8);
+
+    let mut out = Vec::with_capacity(PSF2_HEADER_SIZE + chars.len() * (glyph_size + 2));
+    out.extend_from_slice(&PSF2_MAGIC);
+    for value in [
+        0,
+        PSF2_HEADER_SIZE,
+        PSF2_HAS_UNICODE_TABLE as usize,
+        chars.len(),
+        glyph_size,
+        height,
+        width,
+    ] {
+        out.extend_from_slice(&(value as u32).to_le_bytes());
+    }
+    for &ch in &chars {
+        encode_glyph(font, font.get_pattern(ch), width, &mut out);
+    }
+    let mut buffer = [0u8; 4];
+    for &ch in &chars {
+        out.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+        out.push(PSF2_SEPARATOR);
+    }
+    writer.write_all(&out)
+}
+
+/// Write a font as PSF1 with a unicode table
+///
+/// PSF1 glyphs are always 8 pixels wide and a font holds at most 512 of
+/// them, all mapped to characters in the Basic Multilingual Plane.
+pub(crate) fn write_psf1<W: Write>(font: &PixelFont, mut writer: W) -> io::Result<()> {
+    let chars = export_chars(font);
+    let height = font.height();
+    if height > u8::MAX as usize {
+        return Err(invalid_input("PSF1 glyphs are at most 255 rows tall"));
+    }
+    if font.characters.values().any(|pattern| pattern.width() > 8) {
+        return Err(invalid_input("PSF1 glyphs are at most 8 pixels wide"));
+    }
+    if chars.iter().any(|&ch| ch as u32 >= PSF1_STARTSEQ as u32) {
+        return Err(invalid_input("PSF1 can only map characters below U+FFFE"));
+    }
+    let (mode, count) = match chars.len() {
+        0..=256 => (PSF1_MODEHASTAB, 256),
+        257..=512 => (PSF1_MODEHASTAB | PSF1_MODE512, 512),
+        _ => return Err(invalid_input("PSF1 fonts hold at most 512 glyphs")),
+    };
+
+    let mut out = Vec::with_capacity(4 + count * (height + 4));
+    out.extend_from_slice(&PSF1_MAGIC);
+    out.push(mode);
+    out.push(height as u8);
+    for index in 0..count {
+        let pattern = chars.get(index).and_then(|&ch| font.get_pattern(ch));
+        encode_glyph(font, pattern, 8, &mut out);
+    }
+    for index in 0..count {
+        if let Some(&ch) = chars.get(index) {
+            out.extend_from_slice(&(ch as u16).to_le_bytes());
+        }
+        out.extend_from_slice(&PSF1_SEPARATOR.to_le_bytes());
+    }
+    writer.write_all(&out)
+}
+
+fn invalid(message: &str) -> FontError {
+    FontError::InvalidFormat(message.to_string())
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_psf2_round_trip() {
+        let font = PixelFont::new();
+        let mut data = Vec::new();
+        write_psf2(&font, &mut data).unwrap();
+        assert!(data.starts_with(&PSF2_MAGIC));
+
+        let imported = parse(data.as_slice()).unwrap();
+        assert_eq!(imported.height(), 5);
+        assert_eq!(imported.supported_characters(), font.supported_characters());
+
+        // Glyphs come back padded to the widest glyph of the font
+        let i = imported.get_pattern('i').unwrap();
+        assert_eq!(i.width(), 5);
+        assert_eq!(i.to_rows()[0], vec![1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_psf1_round_trip() {
+        let font = PixelFont::new();
+        let mut data = Vec::new();
+        write_psf1(&font, &mut data).unwrap();
+        assert_eq!(&data[..4], &[0x36, 0x04, PSF1_MODEHASTAB, 5]);
+
+        let imported = parse(data.as_slice()).unwrap();
+        assert_eq!(imported.supported_characters(), font.supported_characters());
+        assert_eq!(
+            imported.get_pattern('A').unwrap().to_rows()[0],
+            vec![0, 1, 1, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_psf1_unicode_table() {
+        // Two-row glyphs; glyph 0 maps to 'é' and a skipped sequence, glyph 1 to 'Ж'
+        let mut data = vec![0x36, 0x04, PSF1_MODEHASTAB, 2];
+        data.extend_from_slice(&[0x80, 0x40, 0xC0, 0x00]);
+        data.resize(4 + 256 * 2, 0);
+        for entry in [
+            0x00E9,
+            PSF1_STARTSEQ,
+            0x0065,
+            0x0301,
+            PSF1_SEPARATOR,
+            0x0416,
+            PSF1_SEPARATOR,
+        ] {
+            data.extend_from_slice(&u16::to_le_bytes(entry));
+        }
+        for _ in 2..256 {
+            data.extend_from_slice(&PSF1_SEPARATOR.to_le_bytes());
+        }
+
+        let font = parse(data.as_slice()).unwrap();
+        assert_eq!(font.supported_characters(), vec!['é', 'Ж']);
+        assert_eq!(
+            font.get_pattern('é').unwrap().to_rows()[1],
+            vec![0, 1, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            font.get_pattern('Ж').unwrap().to_rows()[0],
+            vec![1, 1, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_psf2_without_table_uses_glyph_index() {
+        let mut data = PSF2_MAGIC.to_vec();
+        for value in [0u32, 32, 0, 66, 1, 1, 3] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.resize(32 + 65, 0);
+        data.push(0xA0);
+
+        let font = parse(data.as_slice()).unwrap();
+        assert_eq!(font.get_pattern('A').unwrap().to_rows(), vec![vec![1, 0, 1]]);
+        assert_eq!(font.height(), 1);
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert_eq!(
+            parse(&b"hello"[..]).unwrap_err(),
+            FontError::InvalidFormat("not a PSF font".to_string())
+        );
+        assert_eq!(
+            parse(&[0x36, 0x04, 0, 8, 0][..]).unwrap_err(),
+            FontError::InvalidFormat("truncated PSF1 glyph data".to_string())
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    Bitmap, LossyRender, Measurement, PixelArtError, PixelFont, RenderOptions, WrappedText,
+};
+
+/// A font and a set of options, ready to render many strings
+///
+/// The font is borrowed rather than copied, so creating a renderer is cheap
+/// and no font is rebuilt between renders. [`Renderer::default`] uses the
+/// shared [`PixelFont::builtin`] font, which is built once on first use.
+#[derive(Debug, Clone)]
+pub struct Renderer<'a> {
+    font: &'a PixelFont,
+    options: RenderOptions,
+}
+
+impl<'a> Renderer<'a> {
+    /// Create a renderer for `font` with the default options
+    pub fn new(font: &'a PixelFont) -> Self {
+        Self {
+            font,
+            options: RenderOptions::default(),
+        }
+    }
+
+    /// Set the options used for every render
+    pub fn with_options(mut self, options: RenderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The font text is rendered with
+    pub fn font(&self) -> &'a PixelFont {
+        self.font
+    }
+
+    /// The options text is rendered with
+    pub fn options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    /// Render text into a bitmap
+    ///
+    /// See [`PixelFont::render`].
+    pub fn render(&self, text: &str) -> Result<Bitmap, PixelArtError> {
+        self.font.render(text, &self.options)
+    }
+
+    /// Render text, drawing a replacement for every unsupported character
+    ///
+    /// See [`PixelFont::render_lossy`].
+    pub fn render_lossy(&self, text: &str) -> Result<LossyRender, PixelArtError> {
+        self.font.render_lossy(text, &self.options)
+    }
+
+    /// Work out the size and character positions of rendered text
+    ///
+    /// See [`PixelFont::measure`].
+    pub fn measure(&self, text: &str) -> Result<Measurement, PixelArtError> {
+        self.font.measure(text, &self.options)
+    }
+
+    /// Work out how text would be split into lines without rendering it
+    ///
+    /// See [`PixelFont::wrap_text`].
+    pub fn wrap_text(&self, text: &str) -> Result<WrappedText, PixelArtError> {
+        self.font.wrap_text(text, &self.options)
+    }
+}
+
+impl Default for Renderer<'static> {
+    fn default() -> Self {
+        Self::new(PixelFont::builtin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_font_is_shared() {
+        assert!(std::ptr::eq(PixelFont::builtin(), PixelFont::builtin()));
+        assert_eq!(PixelFont::builtin(), &PixelFont::new());
+        assert!(std::ptr::eq(
+            Renderer::default().font(),
+            PixelFont::builtin()
+        ));
+    }
+
+    #[test]
+    fn test_renders_like_the_font() {
+        let options = RenderOptions::new().padding(0).letter_spacing(2);
+        let renderer = Renderer::default().with_options(options.clone());
+        for text in ["Hi", "Hello\nWorld", ""] {
+            assert_eq!(
+                renderer.render(text),
+                PixelFont::new().render(text, &options)
+            );
+        }
+        assert_eq!(
+            renderer.measure("Hi").unwrap().width,
+            renderer.render("Hi").unwrap().width()
+        );
+    }
+
+    #[test]
+    fn test_borrows_custom_font() {
+        let font: PixelFont = "height 1\nchar x\n#\n".parse().unwrap();
+        let renderer = Renderer::new(&font);
+        assert_eq!(renderer.options(), &RenderOptions::default());
+        assert!(renderer.render("x").is_ok());
+        assert_eq!(
+            renderer.render("y"),
+            Err(PixelArtError::UnsupportedCharacter('y'))
+        );
+        assert!(renderer.render_lossy("y").is_ok());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::layout::GlyphSource;
+use crate::{
+    diacritic, lossy, translit, validate, Bitmap, CharacterPattern, LossyRender, Measurement,
+    PixelArtError, PixelFont, RenderOptions, Transliterated, ValidationReport, WrappedText,
+};
+
+/// An ordered list of fonts rendered as one
+///
+/// Each character is drawn with the first font that has a glyph for it, so a
+/// primary font can be backed by symbol or other-script fonts. Glyphs from
+/// different fonts share a baseline; lines are as tall as the largest ascent
+/// plus the largest descent of all the fonts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontStack {
+    fonts: Vec<PixelFont>,
+}
+
+impl FontStack {
+    /// Create a stack with a single primary font
+    pub fn new(primary: PixelFont) -> Self {
+        Self {
+            fonts: vec![primary],
+        }
+    }
+
+    /// Add a font consulted after all the fonts already in the stack
+    pub fn with_fallback(mut self, font: PixelFont) -> Self {
+        self.push(font);
+        self
+    }
+
+    /// Add a font consulted after all the fonts already in the stack
+    pub fn push(&mut self, font: PixelFont) {
+        self.fonts.push(font);
+    }
+
+    /// The fonts in the order they are consulted
+    pub fn fonts(&self) -> &[PixelFont] {
+        &self.fonts
+    }
+
+    /// Get the pattern for `ch` from the first font that has one
+    pub fn get_pattern(&self, ch: char) -> Option<&CharacterPattern> {
+        self.fonts.iter().find_map(|font| font.get_pattern(ch))
+    }
+
+    /// Returns true if any font in the stack has a pattern for `ch`
+    pub fn supports_char(&self, ch: char) -> bool {
+        self.fonts.iter().any(|font| font.supports_char(ch))
+    }
+
+    /// Every character supported by at least one font, sorted
+    pub fn supported_characters(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self
+            .fonts
+            .iter()
+            .flat_map(PixelFont::supported_characters)
+            .collect();
+        chars.sort();
+        chars.dedup();
+        chars
+    }
+
+    /// Largest number of rows above the baseline of any font
+    pub fn ascent(&self) -> usize {
+        self.fonts.iter().map(PixelFont::ascent).max().unwrap_or(0)
+    }
+
+    /// Largest number of rows below the baseline of any font
+    pub fn descent(&self) -> usize {
+        self.fonts.iter().map(PixelFont::descent).max().unwrap_or(0)
+    }
+
+    /// Height of a line of text, in rows
+    pub fn height(&self) -> usize {
+        self.ascent() + self.descent()
+    }
+
+    /// Render text into a bitmap, falling back through the fonts for each character
+    ///
+    /// See [`PixelFont::render`].
+    pub fn render(&self, text: &str, options: &RenderOptions) -> Result<Bitmap, PixelArtError> {
+        crate::render_glyphs(self, text, options)
+    }
+
+    /// Render text, drawing a replacement for characters no font supports
+    ///
+    /// See [`PixelFont::render_lossy`].
+    pub fn render_lossy(
+        &self,
+        text: &str,
+        options: &RenderOptions,
+    ) -> Result<LossyRender, PixelArtError> {
+        lossy::render(self, text, options)
+    }
+
+    /// Work out how text would be split into lines without rendering it
+    ///
+    /// See [`PixelFont::wrap_text`].
+    pub fn wrap_text(
+        &self,
+        text: &str,
+        options: &RenderOptions,
+    ) -> Result<WrappedText, PixelArtError> {
+        crate::wrap_glyphs(self, text, options)
+    }
+
+    /// Work out the size and character positions of rendered text
+    ///
+    /// See [`PixelFont::measure`].
+    pub fn measure(
+        &self,
+        text: &str,
+        options: &RenderOptions,
+    ) -> Result<Measurement, PixelArtError> {
+        crate::measure_glyphs(self, text, options)
+    }
+
+    /// Build an accented glyph from the first font with the base letter
+    ///
+    /// See [`PixelFont::compose`].
+    pub fn compose(&self, ch: char) -> Option<CharacterPattern> {
+        diacritic::compose_char(self, ch)
+    }
+
+    /// Find every character in `text` that no font in the stack can render
+    pub fn validate(&self, text: &str) -> ValidationReport {
+        validate::validate(text, self)
+    }
+
+    /// Replace characters no font can draw with fallback spellings
+    ///
+    /// See [`PixelFont::transliterate`].
+    pub fn transliterate(&self, text: &str) -> Transliterated {
+        translit::transliterate(text, self)
+    }
+}
+
+impl From<PixelFont> for FontStack {
+    fn from(font: PixelFont) -> Self {
+        Self::new(font)
+    }
+}
+
+impl GlyphSource for FontStack {
+    fn glyph(&self, ch: char) -> Option<&CharacterPattern> {
+        self.get_pattern(ch)
+    }
+
+    fn ascent(&self) -> usize {
+        self.ascent()
+    }
+
+    fn descent(&self) -> usize {
+        self.descent()
+    }
+
+    /// Pairs are only kerned when both glyphs come from the same font
+    fn kerning(&self, left: char, right: char) -> isize {
+        let source = |ch| self.fonts.iter().position(|font| font.supports_char(ch));
+        match (source(left), source(right)) {
+            (Some(l), Some(r)) if l == r => self.fonts[l].kerning(left, right),
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 7-row font with a descender and a '€' glyph
+    fn symbols() -> PixelFont {
+        "height 7\nbaseline 6\nchar €\n.##\n#..\n###\n#..\n###\n#..\n.##\n"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_falls_back_in_order() {
+        let mut symbols = symbols();
+        symbols.insert('A', CharacterPattern::new(&[&[1]]));
+        let stack = FontStack::new(PixelFont::new()).with_fallback(symbols);
+
+        assert_eq!(stack.get_pattern('A'), PixelFont::new().get_pattern('A'));
+        assert!(stack.get_pattern('€').is_some());
+        assert!(!stack.supports_char('雪'));
+
+        let chars = stack.supported_characters();
+        assert_eq!(
+            chars.len(),
+            PixelFont::new().supported_characters().len() + 1
+        );
+        assert!(chars.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_mixed_fonts_share_baseline() {
+        let stack = FontStack::new(PixelFont::new()).with_fallback(symbols());
+        assert_eq!((stack.ascent(), stack.descent()), (6, 1));
+
+        let options = RenderOptions::new().padding(0);
+        let bitmap = stack.render("I€", &options).unwrap();
+        assert_eq!(bitmap.height(), 7);
+
+        // The 5-row 'I' sits on the baseline, one row below the top of '€'
+        let column: Vec<bool> = bitmap.column(0).collect();
+        assert_eq!(column, vec![false, true, true, true, true, true, false]);
+        assert_eq!(bitmap.get(4, 6), Some(true));
+
+        let report = stack.validate("I€雪");
+        assert_eq!(report.unsupported().len(), 1);
+    }
+}
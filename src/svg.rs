@@ -0,0 +1,308 @@
+use std::fmt::Write as _;
+use std::io;
+
+use crate::{Bitmap, Rgba};
+
+/// How lit pixels are drawn in SVG output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SvgShape {
+    /// One `<rect>` per lit pixel, keeping gaps and rounded corners on every cell
+    #[default]
+    Rects,
+    /// One `<rect>` per horizontal run of lit pixels; gaps inside a run are
+    /// filled and only the ends of a run are rounded
+    Runs,
+    /// A single `<path>` tracing every run, the most compact output; corners
+    /// are never rounded
+    Path,
+}
+
+/// Settings for SVG output
+///
+/// Built with chained setters starting from [`SvgOptions::new`]. Sizes are in
+/// SVG user units; the image also gets a matching `viewBox` so it scales.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgOptions {
+    pub(crate) cell_size: usize,
+    pub(crate) gap: usize,
+    pub(crate) radius: usize,
+    pub(crate) on: Rgba,
+    pub(crate) off: Rgba,
+    pub(crate) shape: SvgShape,
+    pub(crate) title: Option<String>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 10,
+            gap: 0,
+            radius: 0,
+            on: Rgba::BLACK,
+            off: Rgba::TRANSPARENT,
+            shape: SvgShape::Rects,
+            title: None,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// Create options for 10-unit black square cells on a transparent background
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the width and height of each cell; 0 is treated as 1
+    pub fn cell_size(mut self, size: usize) -> Self {
+        self.cell_size = size.max(1);
+        self
+    }
+
+    /// Leave `gap` units between neighbouring cells
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Round the corners of each cell; half the cell size draws round "LED" dots
+    pub fn corner_radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Set the colour of lit pixels
+    pub fn on_color(mut self, color: Rgba) -> Self {
+        self.on = color;
+        self
+    }
+
+    /// Set the background colour; transparent backgrounds are left out
+    pub fn off_color(mut self, color: Rgba) -> Self {
+        self.off = color;
+        self
+    }
+
+    /// Choose how lit pixels are grouped into shapes
+    pub fn shape(mut self, shape: SvgShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Add a `<title>`, usually the rendered text, as the accessible name of the image
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// Escape text for use in XML content, dropping characters XML cannot hold
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters are not allowed in XML at all
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            _ if ch.is_control() => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// `fill` and, for translucent colours, `fill-opacity` attributes
+fn fill(color: Rgba) -> String {
+    let mut attributes = format!("fill=\"#{:02x}{:02x}{:02x}\"", color.r, color.g, color.b);
+    if !color.is_opaque() {
+        let opacity = f64::from(color.a) / 255.0;
+        let _ = write!(attributes, " fill-opacity=\"{:.3}\"", opacity);
+    }
+    attributes
+}
+
+/// Horizontal runs of lit pixels as `(row, first column, length)`
+fn runs(bitmap: &Bitmap) -> Vec<(usize, usize, usize)> {
+    let mut runs = Vec::new();
+    for (y, row) in bitmap.rows().enumerate() {
+        let mut start = None;
+        for (x, pixel) in row.chain([false]).enumerate() {
+            match (pixel, start) {
+                (true, None) => start = Some(x),
+                (false, Some(first)) => {
+                    runs.push((y, first, x - first));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    runs
+}
+
+/// Render `bitmap` as an SVG document
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if the image size overflows.
+pub(crate) fn to_svg(bitmap: &Bitmap, options: &SvgOptions) -> io::Result<String> {
+    let checked_size = |cells: usize| {
+        let gaps = cells.saturating_sub(1).checked_mul(options.gap)?;
+        cells.checked_mul(options.cell_size)?.checked_add(gaps)
+    };
+    let (Some(width), Some(height)) = (checked_size(bitmap.width()), checked_size(bitmap.height()))
+    else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "SVG image is too large for the cell size and gap",
+        ));
+    };
+    // Every position and run length is within the image size checked above;
+    // the gap may only be huge when there is a single cell and so no pitch
+    let pitch = options.cell_size.saturating_add(options.gap);
+    let size = |cells: usize| cells * options.cell_size + cells.saturating_sub(1) * options.gap;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\" role=\"img\">",
+        width, height
+    );
+    if let Some(title) = &options.title {
+        let _ = writeln!(svg, "<title>{}</title>", escape(title));
+    }
+    if options.off.a > 0 {
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" {}/>",
+            width,
+            height,
+            fill(options.off)
+        );
+    }
+
+    let corners = match options.radius {
+        0 => String::new(),
+        radius => format!(" rx=\"{0}\" ry=\"{0}\"", radius),
+    };
+    let _ = writeln!(svg, "<g {}>", fill(options.on));
+    match options.shape {
+        SvgShape::Rects => {
+            for (y, x, length) in runs(bitmap) {
+                for x in x..x + length {
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\"{3}/>",
+                        x * pitch,
+                        y * pitch,
+                        options.cell_size,
+                        corners
+                    );
+                }
+            }
+        }
+        SvgShape::Runs => {
+            for (y, x, length) in runs(bitmap) {
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>",
+                    x * pitch,
+                    y * pitch,
+                    size(length),
+                    options.cell_size,
+                    corners
+                );
+            }
+        }
+        SvgShape::Path => {
+            let mut path = String::new();
+            for (y, x, length) in runs(bitmap) {
+                let _ = write!(
+                    path,
+                    "M{} {}h{}v{}h-{}z",
+                    x * pitch,
+                    y * pitch,
+                    size(length),
+                    options.cell_size,
+                    size(length)
+                );
+            }
+            if !path.is_empty() {
+                let _ = writeln!(svg, "<path d=\"{}\"/>", path);
+            }
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Bitmap {
+        let mut bitmap = Bitmap::new(3, 2);
+        bitmap.set(0, 0, true);
+        bitmap.set(1, 0, true);
+        bitmap.set(2, 1, true);
+        bitmap
+    }
+
+    #[test]
+    fn test_rects_per_pixel() {
+        let options = SvgOptions::new().cell_size(4).gap(1).corner_radius(2);
+        let svg = to_svg(&sample(), &options).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"14\" height=\"9\" viewBox=\"0 0 14 9\""));
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains("<rect x=\"5\" y=\"0\" width=\"4\" height=\"4\" rx=\"2\" ry=\"2\"/>"));
+        assert!(svg.contains("<rect x=\"10\" y=\"5\" width=\"4\" height=\"4\" rx=\"2\" ry=\"2\"/>"));
+        assert!(svg.ends_with("</g>\n</svg>\n"));
+    }
+
+    #[test]
+    fn test_runs_and_path() {
+        let options = SvgOptions::new().cell_size(4).gap(1);
+        let runs = to_svg(&sample(), &options.clone().shape(SvgShape::Runs)).unwrap();
+        assert_eq!(runs.matches("<rect").count(), 2);
+        assert!(runs.contains("<rect x=\"0\" y=\"0\" width=\"9\" height=\"4\"/>"));
+
+        let path = to_svg(&sample(), &options.shape(SvgShape::Path)).unwrap();
+        assert!(path.contains("<path d=\"M0 0h9v4h-9zM10 5h4v4h-4z\"/>"));
+        assert!(!path.contains("<rect"));
+    }
+
+    #[test]
+    fn test_title_and_colours() {
+        let options = SvgOptions::new()
+            .title("Fish & <Chips>\u{7}")
+            .on_color(Rgba::new(255, 0, 0, 128))
+            .off_color(Rgba::WHITE);
+        let svg = to_svg(&sample(), &options).unwrap();
+        assert!(svg.contains("<title>Fish &amp; &lt;Chips&gt;</title>"));
+        assert!(svg.contains("<rect width=\"30\" height=\"20\" fill=\"#ffffff\"/>"));
+        assert!(svg.contains("<g fill=\"#ff0000\" fill-opacity=\"0.502\">"));
+
+        let plain = to_svg(&sample(), &SvgOptions::new()).unwrap();
+        assert!(!plain.contains("<title>"));
+        assert_eq!(plain.matches("<rect").count(), 3);
+    }
+
+    #[test]
+    fn test_oversized_images_are_rejected() {
+        for options in [
+            SvgOptions::new().cell_size(usize::MAX / 2),
+            SvgOptions::new().gap(usize::MAX),
+        ] {
+            let error = to_svg(&sample(), &options).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+
+        // A gap between cells that do not exist is never drawn
+        let mut single = Bitmap::new(1, 1);
+        single.set(0, 0, true);
+        let svg = to_svg(&single, &SvgOptions::new().gap(usize::MAX)).unwrap();
+        assert!(svg.contains("viewBox=\"0 0 10 10\""));
+    }
+}
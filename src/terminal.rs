@@ -0,0 +1,214 @@
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal};
+
+use crate::{Bitmap, Rgba};
+
+/// Which characters draw the bitmap in a terminal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TerminalStyle {
+    /// Two full blocks (`██`) per pixel so pixels come out roughly square
+    Blocks,
+    /// Half blocks (`▀`, `▄`, `█`), two pixel rows per line of text
+    #[default]
+    HalfBlocks,
+    /// Braille patterns, 2 by 4 pixels per character
+    Braille,
+}
+
+/// When to colour terminal output with ANSI escape codes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Colour only when stdout is a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    /// Always emit escape codes
+    Always,
+    /// Never emit escape codes
+    Never,
+}
+
+impl ColorMode {
+    /// Whether output written to stdout right now should be coloured
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+/// Settings for drawing a bitmap as terminal text
+///
+/// Built with chained setters starting from [`TerminalOptions::new`]. Without
+/// colours the terminal's own foreground draws lit pixels.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalOptions {
+    pub(crate) style: TerminalStyle,
+    pub(crate) on: Option<Rgba>,
+    pub(crate) off: Option<Rgba>,
+    pub(crate) color: ColorMode,
+}
+
+impl TerminalOptions {
+    /// Create options for uncoloured half blocks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose the characters used to draw pixels
+    pub fn style(mut self, style: TerminalStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Draw lit pixels in `color`
+    ///
+    /// Terminal colours have no transparency, so only the red, green and blue
+    /// channels are used.
+    pub fn on_color(mut self, color: Rgba) -> Self {
+        self.on = Some(color);
+        self
+    }
+
+    /// Fill unlit pixels with `color`; fully transparent keeps the terminal background
+    pub fn off_color(mut self, color: Rgba) -> Self {
+        self.off = Some(color).filter(|color| color.a > 0);
+        self
+    }
+
+    /// Choose when the colours are actually used
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color = mode;
+        self
+    }
+}
+
+/// The character for a half-block cell from its top and bottom pixels
+fn half_block(top: bool, bottom: bool) -> char {
+    match (top, bottom) {
+        (true, true) => '█',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (false, false) => ' ',
+    }
+}
+
+/// Bit of each dot in a braille pattern, indexed by row then column
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// The braille character covering the 2 by 4 pixels with `(left, top)` at the corner
+fn braille(bitmap: &Bitmap, left: usize, top: usize) -> char {
+    let mut bits = 0;
+    for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+        for (dx, bit) in row.iter().enumerate() {
+            if bitmap.get(left + dx, top + dy) == Some(true) {
+                bits |= bit;
+            }
+        }
+    }
+    char::from_u32(0x2800 + bits).unwrap_or(' ')
+}
+
+/// The text lines drawing `bitmap`, without colour
+fn lines(bitmap: &Bitmap, style: TerminalStyle) -> Vec<String> {
+    let lit = |x, y| bitmap.get(x, y) == Some(true);
+    match style {
+        TerminalStyle::Blocks => bitmap
+            .rows()
+            .map(|row| row.map(|pixel| if pixel { "██" } else { "  " }).collect())
+            .collect(),
+        TerminalStyle::HalfBlocks => (0..bitmap.height())
+            .step_by(2)
+            .map(|y| {
+                (0..bitmap.width())
+                    .map(|x| half_block(lit(x, y), lit(x, y + 1)))
+                    .collect()
+            })
+            .collect(),
+        TerminalStyle::Braille => (0..bitmap.height())
+            .step_by(4)
+            .map(|y| {
+                (0..bitmap.width())
+                    .step_by(2)
+                    .map(|x| braille(bitmap, x, y))
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+/// Draw `bitmap` as terminal text, one line per row of characters
+pub(crate) fn to_terminal(bitmap: &Bitmap, options: &TerminalOptions) -> String {
+    let mut escape = String::new();
+    if options.color.enabled() {
+        if let Some(color) = options.on {
+            let _ = write!(escape, "\x1b[38;2;{};{};{}m", color.r, color.g, color.b);
+        }
+        if let Some(color) = options.off {
+            let _ = write!(escape, "\x1b[48;2;{};{};{}m", color.r, color.g, color.b);
+        }
+    }
+
+    let mut out = String::new();
+    for line in lines(bitmap, options.style) {
+        if escape.is_empty() {
+            out.push_str(&line);
+        } else {
+            // Reset before the newline so the colour does not bleed into the next line
+            let _ = write!(out, "{}{}\x1b[0m", escape, line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3x3 bitmap with the corners and centre lit
+    fn sample() -> Bitmap {
+        let mut bitmap = Bitmap::new(3, 3);
+        for (x, y) in [(0, 0), (2, 0), (1, 1), (0, 2), (2, 2)] {
+            bitmap.set(x, y, true);
+        }
+        bitmap
+    }
+
+    fn draw(style: TerminalStyle) -> String {
+        to_terminal(&sample(), &TerminalOptions::new().style(style))
+    }
+
+    #[test]
+    fn test_styles() {
+        assert_eq!(draw(TerminalStyle::Blocks), "██  ██\n  ██  \n██  ██\n");
+        assert_eq!(draw(TerminalStyle::HalfBlocks), "▀▄▀\n▀ ▀\n");
+        // Dots 1, 3 and 5 in the first cell, then dots 1 and 3 in the half-empty second
+        assert_eq!(draw(TerminalStyle::Braille), "\u{2815}\u{2805}\n");
+        assert_eq!(to_terminal(&Bitmap::new(0, 0), &TerminalOptions::new()), "");
+    }
+
+    #[test]
+    fn test_colors() {
+        let options = TerminalOptions::new()
+            .on_color(Rgba::rgb(255, 0, 0))
+            .off_color(Rgba::rgb(0, 0, 64))
+            .color_mode(ColorMode::Always);
+        let mut bitmap = Bitmap::new(1, 2);
+        bitmap.set(0, 0, true);
+        assert_eq!(
+            to_terminal(&bitmap, &options),
+            "\x1b[38;2;255;0;0m\x1b[48;2;0;0;64m▀\x1b[0m\n"
+        );
+
+        let never = options.clone().color_mode(ColorMode::Never);
+        assert_eq!(to_terminal(&bitmap, &never), "▀\n");
+        let transparent = options.off_color(Rgba::TRANSPARENT);
+        assert_eq!(
+            to_terminal(&bitmap, &transparent),
+            "\x1b[38;2;255;0;0m▀\x1b[0m\n"
+        );
+    }
+}
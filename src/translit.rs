@@ -0,0 +1,388 @@
+//! Fallback spellings for characters a font cannot draw
+//!
+//! Accented letters are reduced to their base letter using a table generated
+//! from the canonical decompositions in the Unicode Character Database, with
+//! the combining marks dropped. Letters and punctuation that do not decompose
+//! get the usual ASCII stand-ins instead, such as `ß` → `ss`, `Đ` → `D` or
+//! curly quotes → straight quotes.
+
+use crate::layout::GlyphSource;
+use crate::validate::{self, UnsupportedChar};
+
+/// Every character whose canonical decomposition (NFD) is a base character
+/// followed only by combining marks, grouped by that base
+///
+/// Generated from the Unicode Character Database for Latin, Greek and Cyrillic
+/// (U+00C0–U+024F, U+0370–U+04FF and U+1E00–U+1FFF). Decompositions are
+/// followed all the way down, so `ễ` is listed under `e` even though it
+/// decomposes to `ê` plus a tilde first.
+const ACCENTED: &[(char, &str)] = &[
+    (';', "\u{37e}"),
+    ('A', "ÀÁÂÃÄÅĀĂĄǍǞǠǺȀȂȦḀẠẢẤẦẨẪẬẮẰẲẴẶ"),
+    ('B', "ḂḄḆ"),
+    ('C', "ÇĆĈĊČḈ"),
+    ('D', "ĎḊḌḎḐḒ"),
+    ('E', "ÈÉÊËĒĔĖĘĚȄȆȨḔḖḘḚḜẸẺẼẾỀỂỄỆ"),
+    ('F', "Ḟ"),
+    ('G', "ĜĞĠĢǦǴḠ"),
+    ('H', "ĤȞḢḤḦḨḪ"),
+    ('I', "ÌÍÎÏĨĪĬĮİǏȈȊḬḮỈỊ"),
+    ('J', "Ĵ"),
+    ('K', "ĶǨḰḲḴ"),
+    ('L', "ĹĻĽḶḸḺḼ"),
+    ('M', "ḾṀṂ"),
+    ('N', "ÑŃŅŇǸṄṆṈṊ"),
+    ('O', "ÒÓÔÕÖŌŎŐƠǑǪǬȌȎȪȬȮȰṌṎṐṒỌỎỐỒỔỖỘỚỜỞỠỢ"),
+    ('P', "ṔṖ"),
+    ('R', "ŔŖŘȐȒṘṚṜṞ"),
+    ('S', "ŚŜŞŠȘṠṢṤṦṨ"),
+    ('T', "ŢŤȚṪṬṮṰ"),
+    ('U', "ÙÚÛÜŨŪŬŮŰŲƯǓǕǗǙǛȔȖṲṴṶṸṺỤỦỨỪỬỮỰ"),
+    ('V', "ṼṾ"),
+    ('W', "ŴẀẂẄẆẈ"),
+    ('X', "ẊẌ"),
+    ('Y', "ÝŶŸȲẎỲỴỶỸ"),
+    ('Z', "ŹŻŽẐẒẔ"),
+    ('a', "àáâãäåāăąǎǟǡǻȁȃȧḁạảấầẩẫậắằẳẵặ"),
+    ('b', "ḃḅḇ"),
+    ('c', "çćĉċčḉ"),
+    ('d', "ďḋḍḏḑḓ"),
+    ('e', "èéêëēĕėęěȅȇȩḕḗḙḛḝẹẻẽếềểễệ"),
+    ('f', "ḟ"),
+    ('g', "ĝğġģǧǵḡ"),
+    ('h', "ĥȟḣḥḧḩḫẖ"),
+    ('i', "ìíîïĩīĭįǐȉȋḭḯỉị"),
+    ('j', "ĵǰ"),
+    ('k', "ķǩḱḳḵ"),
+    ('l', "ĺļľḷḹḻḽ"),
+    ('m', "ḿṁṃ"),
+    ('n', "ñńņňǹṅṇṉṋ"),
+    ('o', "òóôõöōŏőơǒǫǭȍȏȫȭȯȱṍṏṑṓọỏốồổỗộớờởỡợ"),
+    ('p', "ṕṗ"),
+    ('r', "ŕŗřȑȓṙṛṝṟ"),
+    ('s', "śŝşšșṡṣṥṧṩ"),
+    ('t', "ţťțṫṭṯṱẗ"),
+    ('u', "ùúûüũūŭůűųưǔǖǘǚǜȕȗṳṵṷṹṻụủứừửữự"),
+    ('v', "ṽṿ"),
+    ('w', "ŵẁẃẅẇẉẘ"),
+    ('x', "ẋẍ"),
+    ('y', "ýÿŷȳẏẙỳỵỷỹ"),
+    ('z', "źżžẑẓẕ"),
+    ('·', "\u{387}"),
+    ('Æ', "ǢǼ"),
+    ('Ø', "Ǿ"),
+    ('æ', "ǣǽ"),
+    ('ø', "ǿ"),
+    ('ſ', "ẛ"),
+    ('Ʒ', "Ǯ"),
+    ('ʒ', "ǯ"),
+    ('ʹ', "ʹ"),
+    ('Α', "ΆἈἉἊἋἌἍἎἏᾈᾉᾊᾋᾌᾍᾎᾏᾸᾹᾺΆᾼ"),
+    ('Ε', "ΈἘἙἚἛἜἝῈΈ"),
+    ('Η', "ΉἨἩἪἫἬἭἮἯᾘᾙᾚᾛᾜᾝᾞᾟῊΉῌ"),
+    ('Ι', "ΊΪἸἹἺἻἼἽἾἿῘῙῚΊ"),
+    ('Ο', "ΌὈὉὊὋὌὍῸΌ"),
+    ('Ρ', "Ῥ"),
+    ('Υ', "ΎΫὙὛὝὟῨῩῪΎ"),
+    ('Ω', "ΏὨὩὪὫὬὭὮὯᾨᾩᾪᾫᾬᾭᾮᾯῺΏῼ"),
+    ('α', "άἀἁἂἃἄἅἆἇὰάᾀᾁᾂᾃᾄᾅᾆᾇᾰᾱᾲᾳᾴᾶᾷ"),
+    ('ε', "έἐἑἒἓἔἕὲέ"),
+    ('η', "ήἠἡἢἣἤἥἦἧὴήᾐᾑᾒᾓᾔᾕᾖᾗῂῃῄῆῇ"),
+    ('ι', "ΐίϊἰἱἲἳἴἵἶἷὶίιῐῑῒΐῖῗ"),
+    ('ο', "όὀὁὂὃὄὅὸό"),
+    ('ρ', "ῤῥ"),
+    ('υ', "ΰϋύὐὑὒὓὔὕὖὗὺύῠῡῢΰῦῧ"),
+    ('ω', "ώὠὡὢὣὤὥὦὧὼώᾠᾡᾢᾣᾤᾥᾦᾧῲῳῴῶῷ"),
+    ('ϒ', "ϓϔ"),
+    ('І', "Ї"),
+    ('А', "ӐӒ"),
+    ('Г', "Ѓ"),
+    ('Е', "ЀЁӖ"),
+    ('Ж', "ӁӜ"),
+    ('З', "Ӟ"),
+    ('И', "ЍЙӢӤ"),
+    ('К', "Ќ"),
+    ('О', "Ӧ"),
+    ('У', "ЎӮӰӲ"),
+    ('Ч', "Ӵ"),
+    ('Ы', "Ӹ"),
+    ('Э', "Ӭ"),
+    ('а', "ӑӓ"),
+    ('г', "ѓ"),
+    ('е', "ѐёӗ"),
+    ('ж', "ӂӝ"),
+    ('з', "ӟ"),
+    ('и', "йѝӣӥ"),
+    ('к', "ќ"),
+    ('о', "ӧ"),
+    ('у', "ўӯӱӳ"),
+    ('ч', "ӵ"),
+    ('ы', "ӹ"),
+    ('э', "ӭ"),
+    ('і', "ї"),
+    ('Ѵ', "Ѷ"),
+    ('ѵ', "ѷ"),
+    ('Ә', "Ӛ"),
+    ('ә', "ӛ"),
+    ('Ө', "Ӫ"),
+    ('ө', "ӫ"),
+];
+
+/// Letters, ligatures and punctuation with a fixed ASCII spelling
+const SPELLED: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('ẞ', "SS"),
+    ('Æ', "AE"),
+    ('æ', "ae"),
+    ('Œ', "OE"),
+    ('œ', "oe"),
+    ('Ø', "O"),
+    ('ø', "o"),
+    ('Đ', "D"),
+    ('đ', "d"),
+    ('Ð', "D"),
+    ('ð', "d"),
+    ('Ł', "L"),
+    ('ł', "l"),
+    ('Þ', "Th"),
+    ('þ', "th"),
+    ('ı', "i"),
+    ('ſ', "s"),
+    ('Ĳ', "IJ"),
+    ('ĳ', "ij"),
+    ('ﬀ', "ff"),
+    ('ﬁ', "fi"),
+    ('ﬂ', "fl"),
+    ('ﬃ', "ffi"),
+    ('ﬄ', "ffl"),
+    ('‘', "'"),
+    ('’', "'"),
+    ('‚', "'"),
+    ('‛', "'"),
+    ('′', "'"),
+    ('“', "\""),
+    ('”', "\""),
+    ('„', "\""),
+    ('‟', "\""),
+    ('″', "\""),
+    ('«', "\""),
+    ('»', "\""),
+    ('‐', "-"),
+    ('‑', "-"),
+    ('‒', "-"),
+    ('–', "-"),
+    ('—', "-"),
+    ('―', "-"),
+    ('−', "-"),
+    ('…', "..."),
+    ('¹', "1"),
+    ('²', "2"),
+    ('³', "3"),
+    ('\u{a0}', " "),
+    ('\u{2002}', " "),
+    ('\u{2003}', " "),
+    ('\u{2009}', " "),
+    ('\u{202f}', " "),
+    ('\u{3000}', " "),
+];
+
+/// A character that was replaced by a fallback spelling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transliteration {
+    /// The character the font could not draw and where it was
+    pub original: UnsupportedChar,
+    /// What it was replaced with, empty for dropped combining marks
+    pub replacement: String,
+}
+
+/// Text with unsupported characters replaced by fallback spellings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transliterated {
+    /// The rewritten text
+    pub text: String,
+    /// Every replacement made, in the order they appear in the original text
+    pub transliterations: Vec<Transliteration>,
+}
+
+/// Text ready for layout, along with what was changed on the way
+pub(crate) struct Prepared {
+    pub(crate) text: String,
+    pub(crate) transliterations: Vec<Transliteration>,
+    /// Unsupported characters left in place (or skipped), positioned in the original text
+    pub(crate) unsupported: Vec<UnsupportedChar>,
+}
+
+impl Prepared {
+    /// The index in `original` of the char each char of the prepared text came
+    /// from, plus one entry for the end of the text
+    ///
+    /// `original` must be the text this was prepared from, with nothing skipped.
+    pub(crate) fn origins(&self, original: &str) -> Vec<usize> {
+        let mut origins = Vec::with_capacity(self.text.len() + 1);
+        let mut transliterations = self.transliterations.iter().peekable();
+        let mut char_count = 0;
+        for (char_index, _) in original.chars().enumerate() {
+            let copies = match transliterations.next_if(|t| t.original.char_index == char_index) {
+                Some(transliteration) => transliteration.replacement.chars().count(),
+                None => 1,
+            };
+            origins.resize(origins.len() + copies, char_index);
+            char_count += 1;
+        }
+        origins.push(char_count);
+        origins
+    }
+}
+
+/// The fallback spelling of `ch`, if it has one
+fn fallback(ch: char) -> Option<String> {
+    // Combining diacritical marks are dropped, leaving the letter before them
+    if ('\u{300}'..='\u{36f}').contains(&ch) {
+        return Some(String::new());
+    }
+    // Fullwidth ASCII forms
+    if ('\u{ff01}'..='\u{ff5e}').contains(&ch) {
+        return char::from_u32(ch as u32 - 0xfee0).map(String::from);
+    }
+    let base = ACCENTED
+        .iter()
+        .find(|(_, accented)| accented.contains(ch))
+        .map_or(ch, |&(base, _)| base);
+    // Bases without an ASCII form of their own, such as `æ` from `ǽ`, are spelled out
+    SPELLED
+        .iter()
+        .find(|&&(from, _)| from == base)
+        .map(|(_, to)| to.to_string())
+        .or_else(|| (base != ch).then(|| base.to_string()))
+}
+
+/// Replace unsupported characters with fallback spellings the font can draw
+///
+/// Characters without a usable fallback are kept, or dropped if `skip` is set,
+/// and listed in [`Prepared::unsupported`].
+pub(crate) fn prepare<G: GlyphSource + ?Sized>(
+    text: &str,
+    font: &G,
+    transliterate: bool,
+    skip: bool,
+) -> Prepared {
+    let mut prepared = Prepared {
+        text: String::with_capacity(text.len()),
+        transliterations: Vec::new(),
+        unsupported: Vec::new(),
+    };
+    let mut issues = validate::unsupported_chars(text, font).peekable();
+    for (char_index, ch) in text.chars().enumerate() {
+        let Some(issue) = issues.next_if(|issue| issue.char_index == char_index) else {
+            prepared.text.push(ch);
+            continue;
+        };
+
+        let replacement = fallback(ch).filter(|replacement| {
+            transliterate
+                && replacement
+                    .chars()
+                    .all(|ch| ch == ' ' || font.glyph(ch).is_some())
+        });
+        match replacement {
+            Some(replacement) => {
+                prepared.text.push_str(&replacement);
+                prepared.transliterations.push(Transliteration {
+                    original: issue,
+                    replacement,
+                });
+            }
+            None => {
+                if !skip {
+                    prepared.text.push(ch);
+                }
+                prepared.unsupported.push(issue);
+            }
+        }
+    }
+    prepared
+}
+
+/// Replace every unsupported character that has a usable fallback spelling
+pub(crate) fn transliterate<G: GlyphSource + ?Sized>(text: &str, font: &G) -> Transliterated {
+    let prepared = prepare(text, font, true, false);
+    Transliterated {
+        text: prepared.text,
+        transliterations: prepared.transliterations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PixelFont;
+
+    fn transliterate(text: &str) -> Prepared {
+        prepare(text, &PixelFont::new(), true, false)
+    }
+
+    #[test]
+    fn test_transliterates_names() {
+        assert_eq!(transliterate("José").text, "Jose");
+        assert_eq!(transliterate("“Bob’s” – ok").text, "\"Bob's\" - ok");
+        assert_eq!(transliterate("Zoë").text, "Zoe");
+        assert_eq!(transliterate("Straße").text, "Strasse");
+        assert_eq!(transliterate("Ｈｉ").text, "Hi");
+        // Vietnamese letters carry two marks, and `Đ` has no decomposition at all
+        assert_eq!(transliterate("Nguyễn").text, "Nguyen");
+        assert_eq!(transliterate("Đặng").text, "Dang");
+        assert_eq!(transliterate("Ǽ").text, "AE");
+        // Decomposed input keeps the letter and drops the combining accent
+        assert_eq!(transliterate("Jose\u{301}").text, "Jose");
+    }
+
+    #[test]
+    fn test_reports_transliterations() {
+        let prepared = transliterate("Straße ñ");
+        assert_eq!(
+            prepared.transliterations,
+            vec![
+                Transliteration {
+                    original: UnsupportedChar {
+                        ch: 'ß',
+                        char_index: 4,
+                        byte_offset: 4
+                    },
+                    replacement: "ss".to_string(),
+                },
+                Transliteration {
+                    original: UnsupportedChar {
+                        ch: 'ñ',
+                        char_index: 7,
+                        byte_offset: 8
+                    },
+                    replacement: "n".to_string(),
+                },
+            ]
+        );
+        assert!(prepared.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_keeps_characters_without_usable_fallback() {
+        let prepared = transliterate("雪 é");
+        assert_eq!(prepared.text, "雪 e");
+        assert_eq!(prepared.transliterations.len(), 1);
+        assert_eq!(
+            prepared.unsupported,
+            vec![UnsupportedChar {
+                ch: '雪',
+                char_index: 0,
+                byte_offset: 0
+            }]
+        );
+
+        // Fallbacks the font cannot draw either are not used
+        let font: PixelFont = "char e\n#\n#\n#\n#\n#\n".parse().unwrap();
+        assert_eq!(prepare("æ", &font, true, false).text, "æ");
+
+        let skipped = prepare("é雪", &PixelFont::new(), false, true);
+        assert_eq!(skipped.text, "");
+        assert_eq!(skipped.unsupported.len(), 2);
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use crate::layout::GlyphSource;
+
+/// A character the font cannot render, with its position in the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedChar {
+    /// The offending character
+    pub ch: char,
+    /// 0-based index of the character among all characters of the text
+    pub char_index: usize,
+    /// 0-based byte offset of the character in the UTF-8 text
+    pub byte_offset: usize,
+}
+
+/// Every unsupported character found in a piece of text
+///
+/// The [`Display`](fmt::Display) output is a caret-style diagnostic pointing
+/// at each problem, suitable for showing to the person who typed the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    text: String,
+    unsupported: Vec<UnsupportedChar>,
+}
+
+/// Columns between tab stops when showing a line in a diagnostic
+const TAB_WIDTH: usize = 8;
+
+impl ValidationReport {
+    /// Returns true if every character can be rendered
+    pub fn is_valid(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    /// The unsupported characters in the order they appear
+    pub fn unsupported(&self) -> &[UnsupportedChar] {
+        &self.unsupported
+    }
+
+    /// The text that was validated
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Render a diagnostic showing each line with a problem and a caret under
+    /// every unsupported character, followed by one line per character
+    ///
+    /// Tabs in the shown line are expanded to spaces, with a stop every
+    /// eight columns, and East Asian wide characters count as two columns, so
+    /// the carets line up in a monospaced terminal. Other control characters
+    /// are shown escaped, like `\u{1b}`, so they cannot disturb the terminal.
+    /// Everything else is assumed to be one column wide.
+    ///
+    /// Returns an empty string if the text is valid.
+    pub fn diagnostic(&self) -> String {
+        let mut out = String::new();
+        let mut issues = self.unsupported.iter().peekable();
+        let mut line_start = 0;
+        for (line_idx, line) in self.text.split('\n').enumerate() {
+            let mut shown = String::new();
+            let mut carets = String::new();
+            let mut columns = 0;
+            for (offset, ch) in line.chars().enumerate() {
+                let is_issue = issues
+                    .next_if(|issue| issue.char_index == line_start + offset)
+                    .is_some();
+                let width = match ch {
+                    '\t' => {
+                        let width = TAB_WIDTH - columns % TAB_WIDTH;
+                        shown.extend(std::iter::repeat_n(' ', width));
+                        width
+                    }
+                    // A supported '\r' is part of a "\r\n" line break
+                    '\r' if !is_issue => continue,
+                    _ if ch.is_control() => {
+                        let escaped = ch.escape_unicode().to_string();
+                        shown.push_str(&escaped);
+                        escaped.len()
+                    }
+                    _ => {
+                        shown.push(ch);
+                        if is_wide(ch) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                };
+                carets.push(if is_issue { '^' } else { ' ' });
+                carets.extend(std::iter::repeat_n(' ', width - 1));
+                columns += width;
+            }
+            let carets = carets.trim_end();
+            if !carets.is_empty() {
+                let prefix = format!("line {}: ", line_idx + 1);
+                out.push_str(&prefix);
+                out.push_str(&shown);
+                out.push('\n');
+                out.extend(std::iter::repeat_n(' ', prefix.len()));
+                out.push_str(carets);
+                out.push('\n');
+            }
+            line_start += line.chars().count() + 1;
+        }
+
+        for issue in &self.unsupported {
+            out.push_str(&format!(
+                "unsupported character {:?} (U+{:04X}) at char {}, byte {}\n",
+                issue.ch, issue.ch as u32, issue.char_index, issue.byte_offset
+            ));
+        }
+        out
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.diagnostic().trim_end())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+/// Whether `ch` takes two columns in a terminal: CJK, Hangul, fullwidth forms and emoji
+fn is_wide(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{1100}'..='\u{115f}'
+            | '\u{2e80}'..='\u{303e}'
+            | '\u{3041}'..='\u{33ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{a000}'..='\u{a4cf}'
+            | '\u{ac00}'..='\u{d7a3}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{fe30}'..='\u{fe4f}'
+            | '\u{ff00}'..='\u{ff60}'
+            | '\u{ffe0}'..='\u{ffe6}'
+            | '\u{1f300}'..='\u{1f64f}'
+            | '\u{1f900}'..='\u{1f9ff}'
+            | '\u{20000}'..='\u{3fffd}'
+    )
+}
+
+/// Find every character of `text` that `font` cannot render
+///
+/// Spaces and line breaks are always supported.
+pub(crate) fn unsupported_chars<'a, G: GlyphSource + ?Sized>(
+    text: &'a str,
+    font: &'a G,
+) -> impl Iterator<Item = UnsupportedChar> + 'a {
+    let mut chars = text.char_indices().enumerate().peekable();
+    std::iter::from_fn(move || {
+        while let Some((char_index, (byte_offset, ch))) = chars.next() {
+            let supported = match ch {
+                ' ' | '\n' => true,
+                '\r' => matches!(chars.peek(), Some((_, (_, '\n')))),
+                _ => font.glyph(ch).is_some(),
+            };
+            if !supported {
+                return Some(UnsupportedChar {
+                    ch,
+                    char_index,
+                    byte_offset,
+                });
+            }
+        }
+        None
+    })
+}
+
+/// Check every character of `text` against `font`
+pub(crate) fn validate<G: GlyphSource + ?Sized>(text: &str, font: &G) -> ValidationReport {
+    ValidationReport {
+        text: text.to_string(),
+        unsupported: unsupported_chars(text, font).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PixelFont;
+
+    #[test]
+    fn test_reports_every_unsupported_char() {
+        let report = validate("José ñ\r\nok\r", &PixelFont::new());
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.unsupported(),
+            &[
+                UnsupportedChar { ch: 'é', char_index: 3, byte_offset: 3 },
+                UnsupportedChar { ch: 'ñ', char_index: 5, byte_offset: 6 },
+                UnsupportedChar { ch: '\r', char_index: 10, byte_offset: 12 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_valid_text() {
+        let report = validate("Hello World\r\nagain", &PixelFont::new());
+        assert!(report.is_valid());
+        assert_eq!(report.diagnostic(), "");
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let report = validate("ok\nJosé ñ", &PixelFont::new());
+        let expected = [
+            "line 2: José ñ",
+            "           ^ ^",
+            "unsupported character 'é' (U+00E9) at char 6, byte 6",
+            "unsupported character 'ñ' (U+00F1) at char 8, byte 9",
+        ];
+        assert_eq!(report.diagnostic(), expected.join("\n") + "\n");
+        assert_eq!(report.to_string(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_diagnostic_columns() {
+        // Tabs are expanded to the next multiple of eight columns
+        let report = validate("a\tbé", &PixelFont::new());
+        let expected = ["line 1: a       bé", "         ^       ^"];
+        assert_eq!(
+            report.diagnostic().lines().take(2).collect::<Vec<_>>(),
+            expected
+        );
+
+        // Control characters are escaped rather than written to the terminal
+        let report = validate("a\u{1b}b\ré", &PixelFont::new());
+        let expected = ["line 1: a\\u{1b}b\\u{d}é", "         ^      ^    ^"];
+        assert_eq!(
+            report.diagnostic().lines().take(2).collect::<Vec<_>>(),
+            expected
+        );
+
+        // Wide characters take two columns
+        let report = validate("雪a雪é", &PixelFont::new());
+        assert_eq!(report.diagnostic().lines().nth(1), Some("        ^  ^ ^"));
+    }
+}
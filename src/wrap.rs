@@ -0,0 +1,296 @@
+use crate::layout::{line_width, GlyphSource};
+use crate::{PixelArtError, RenderOptions};
+
+/// Glyph drawn at the end of a line when a word is split across lines
+const HYPHEN: char = '-';
+
+/// Why a line ended where it did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakKind {
+    /// The input contained a line break
+    Newline,
+    /// The line was wrapped at a space between words
+    Space,
+    /// The line was wrapped after a hyphen already in the text
+    Hyphen,
+    /// A word too long for a single line was split, optionally ending in a hyphen
+    WithinWord {
+        /// Whether a hyphen glyph was appended to the first part
+        hyphenated: bool,
+    },
+}
+
+/// A single line break in wrapped text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineBreak {
+    /// Index of the output line that ends at this break
+    pub line: usize,
+    /// Char index in the original text where the following line starts
+    pub char_index: usize,
+    /// Reason for the break
+    pub kind: BreakKind,
+}
+
+/// Result of wrapping text, useful for previewing a layout before rendering it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedText {
+    /// The text of each output line, including any inserted hyphens
+    pub lines: Vec<String>,
+    /// Every place where one line ends and the next begins
+    pub breaks: Vec<LineBreak>,
+}
+
+impl WrappedText {
+    /// Number of lines the text was laid out on
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// Wrap text so no line is wider than the configured wrap width
+///
+/// Without a wrap width only explicit line breaks split the text.
+pub(crate) fn wrap<G: GlyphSource + ?Sized>(
+    text: &str,
+    font: &G,
+    options: &RenderOptions,
+) -> Result<WrappedText, PixelArtError> {
+    let padding = options.padding_left.saturating_add(options.padding_right);
+    let available = options.wrap_width.map(|width| width.saturating_sub(padding));
+
+    let mut wrapped = WrappedText {
+        lines: Vec::new(),
+        breaks: Vec::new(),
+    };
+    let mut offset = 0;
+    for (idx, raw_line) in text.split('\n').enumerate() {
+        if idx > 0 {
+            wrapped.breaks.push(LineBreak {
+                line: wrapped.lines.len() - 1,
+                char_index: offset,
+                kind: BreakKind::Newline,
+            });
+        }
+
+        let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        let chars: Vec<char> = line.chars().collect();
+        match available {
+            Some(available) => wrap_line(&chars, offset, available, font, options, &mut wrapped)?,
+            None => wrapped.lines.push(line.to_string()),
+        }
+
+        // Skip past the line and the '\n' that ended it
+        offset += raw_line.chars().count() + 1;
+    }
+
+    Ok(wrapped)
+}
+
+/// Greedily wrap a single line of text, appending lines and breaks to `wrapped`
+fn wrap_line<G: GlyphSource + ?Sized>(
+    chars: &[char],
+    offset: usize,
+    available: usize,
+    font: &G,
+    options: &RenderOptions,
+    wrapped: &mut WrappedText,
+) -> Result<(), PixelArtError> {
+    // Lines too wide to measure never fit
+    let fits = |line: &[char]| {
+        line_width(line, font, options).is_ok_and(|width| width <= available)
+    };
+
+    let mut start = 0;
+    loop {
+        let rest = &chars[start..];
+        if fits(rest) {
+            wrapped.lines.push(rest.iter().collect());
+            return Ok(());
+        }
+
+        // Prefer the last space or hyphen that leaves a line narrow enough,
+        // never breaking before the line has anything but spaces on it
+        let mut word_break = None;
+        let mut has_content = false;
+        for end in start + 1..chars.len() {
+            has_content |= chars[end - 1] != ' ';
+            let kind = if chars[end] == ' ' {
+                BreakKind::Space
+            } else if chars[end - 1] == HYPHEN {
+                BreakKind::Hyphen
+            } else {
+                continue;
+            };
+            if !has_content {
+                continue;
+            }
+            if !fits(&chars[start..end]) {
+                break;
+            }
+            word_break = Some((end, kind));
+        }
+
+        let (line, next, kind) = match word_break {
+            Some((end, BreakKind::Hyphen)) => {
+                (chars[start..end].iter().collect(), end, BreakKind::Hyphen)
+            }
+            Some((end, _)) => {
+                let mut next = end;
+                while next < chars.len() && chars[next] == ' ' {
+                    next += 1;
+                }
+                let line: String = chars[start..end].iter().collect();
+                (line.trim_end_matches(' ').to_string(), next, BreakKind::Space)
+            }
+            None => {
+                let (len, hyphenated) = split_word(rest, available, font, options)
+                    .ok_or(PixelArtError::WrapWidthTooSmall(options.wrap_width.unwrap_or(0)))?;
+                let indent = rest.iter().take_while(|&&ch| ch == ' ').count();
+                if len <= indent {
+                    // Indentation leaving no room for the word is dropped, like
+                    // the spaces at any other break
+                    start += indent;
+                    continue;
+                }
+                let mut line: String = rest[..len].iter().collect();
+                if hyphenated {
+                    line.push(HYPHEN);
+                }
+                (line, start + len, BreakKind::WithinWord { hyphenated })
+            }
+        };
+
+        wrapped.lines.push(line);
+        if next >= chars.len() {
+            // Only trailing spaces were left over
+            return Ok(());
+        }
+        wrapped.breaks.push(LineBreak {
+            line: wrapped.lines.len() - 1,
+            char_index: offset + next,
+            kind,
+        });
+        start = next;
+    }
+}
+
+/// Find how many leading characters of an over-long word fit on one line
+///
+/// Returns the number of characters and whether a hyphen follows them, or
+/// `None` if not even a single character fits. No hyphen is added after a
+/// part that already ends in one.
+fn split_word<G: GlyphSource + ?Sized>(
+    word: &[char],
+    available: usize,
+    font: &G,
+    options: &RenderOptions,
+) -> Option<(usize, bool)> {
+    let longest = |hyphen: bool| {
+        let mut best = None;
+        let mut candidate = Vec::with_capacity(word.len() + 1);
+        for (len, &ch) in word.iter().enumerate().take(word.len() - 1) {
+            candidate.truncate(len);
+            candidate.push(ch);
+            if hyphen && ch != HYPHEN {
+                candidate.push(HYPHEN);
+            }
+            if !line_width(&candidate, font, options).is_ok_and(|width| width <= available) {
+                break;
+            }
+            best = Some(len + 1);
+        }
+        best
+    };
+
+    if options.hyphenate && font.glyph(HYPHEN).is_some() {
+        if let Some(len) = longest(true) {
+            return Some((len, word[len - 1] != HYPHEN));
+        }
+    }
+    longest(false).map(|len| (len, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PixelFont;
+
+    fn wrap_default(text: &str, options: &RenderOptions) -> WrappedText {
+        wrap(text, &PixelFont::new(), options).unwrap()
+    }
+
+    #[test]
+    fn test_no_wrap_width_keeps_lines() {
+        let wrapped = wrap_default("ab\ncd", &RenderOptions::new());
+        assert_eq!(wrapped.lines, vec!["ab", "cd"]);
+        assert_eq!(
+            wrapped.breaks,
+            vec![LineBreak { line: 0, char_index: 3, kind: BreakKind::Newline }]
+        );
+    }
+
+    #[test]
+    fn test_wraps_at_spaces() {
+        // "ill" is 1 + 1 + 2 + 1 + 2 = 7 columns, plus 2 columns of padding
+        let options = RenderOptions::new().wrap_width(9);
+        let wrapped = wrap_default("ill ill ill", &options);
+        assert_eq!(wrapped.lines, vec!["ill", "ill", "ill"]);
+        assert_eq!(wrapped.line_count(), 3);
+        assert_eq!(wrapped.breaks[0], LineBreak { line: 0, char_index: 4, kind: BreakKind::Space });
+        assert_eq!(wrapped.breaks[1].char_index, 8);
+    }
+
+    #[test]
+    fn test_splits_long_words() {
+        let options = RenderOptions::new().wrap_width(7);
+        let wrapped = wrap_default("iiiiii", &options);
+        assert_eq!(wrapped.lines, vec!["iii", "iii"]);
+        assert_eq!(wrapped.breaks[0].kind, BreakKind::WithinWord { hyphenated: false });
+
+        // '-' is 3 columns wide, so only "ii" fits in front of it
+        let options = RenderOptions::new().wrap_width(9).hyphenate(true);
+        let wrapped = wrap_default("iiiiiiii", &options);
+        assert_eq!(wrapped.lines, vec!["ii-", "ii-", "iiii"]);
+        assert_eq!(wrapped.breaks[1], LineBreak {
+            line: 1,
+            char_index: 4,
+            kind: BreakKind::WithinWord { hyphenated: true },
+        });
+    }
+
+    #[test]
+    fn test_leading_spaces_never_leave_an_empty_line() {
+        let options = RenderOptions::new().wrap_width(12);
+        // Breaking at the leading spaces would leave an empty first line, and
+        // the word does not fit after them, so they are dropped
+        let wrapped = wrap_default("   hello", &options);
+        assert_eq!(wrapped.lines, vec!["hel", "lo"]);
+
+        // Indentation that leaves room for the word stays
+        let wrapped = wrap_default("  ill ill", &RenderOptions::new().wrap_width(16));
+        assert_eq!(wrapped.lines, vec!["  ill", "ill"]);
+    }
+
+    #[test]
+    fn test_breaks_after_existing_hyphens() {
+        let options = RenderOptions::new().wrap_width(9).hyphenate(true);
+        let wrapped = wrap_default("iiii-iiii", &options);
+        // The second line breaks at the hyphen already there instead of adding one
+        assert_eq!(wrapped.lines, vec!["ii-", "ii-", "iiii"]);
+        assert_eq!(wrapped.breaks[1], LineBreak {
+            line: 1,
+            char_index: 5,
+            kind: BreakKind::Hyphen,
+        });
+
+        let wrapped = wrap_default("iiii-iiii", &RenderOptions::new().wrap_width(13));
+        assert_eq!(wrapped.lines, vec!["iiii-", "iiii"]);
+    }
+
+    #[test]
+    fn test_too_narrow() {
+        let options = RenderOptions::new().wrap_width(4);
+        let result = wrap("W", &PixelFont::new(), &options);
+        assert_eq!(result, Err(PixelArtError::WrapWidthTooSmall(4)));
+    }
+}